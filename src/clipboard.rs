@@ -0,0 +1,21 @@
+use anyhow::Context;
+use arboard::Clipboard;
+use std::cell::RefCell;
+
+thread_local! {
+    // on Linux the copied content is only available as long as the clipboard instance lives
+    static CLIPBOARD: RefCell<Option<Clipboard>> = const { RefCell::new(None) };
+}
+
+pub fn copy(text: &str) -> anyhow::Result<()> {
+    CLIPBOARD.with_borrow_mut(|clipboard| {
+        if clipboard.is_none() {
+            *clipboard = Some(Clipboard::new().context("clipboard not available")?);
+        }
+        clipboard
+            .as_mut()
+            .unwrap()
+            .set_text(text)
+            .context("failed to copy text to clipboard")
+    })
+}
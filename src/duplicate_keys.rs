@@ -0,0 +1,142 @@
+use serde::de::{DeserializeSeed, Deserializer, Error, MapAccess, SeqAccess, Visitor};
+use std::fmt::Formatter;
+
+/// a key, which occurs more than once in an object of a line – serde_json keeps the last value only
+pub struct DuplicateKey {
+    /// path of the key – dotted for nested objects, array items by index, e.g. `items[1].id`
+    pub path: String,
+    /// the values of all occurrences, rendered as JSON
+    pub values: Vec<String>,
+}
+
+/// scans the JSON text for objects with duplicate keys – in any nesting depth; empty for malformed JSON
+pub fn find_duplicate_keys(json: &str) -> Vec<DuplicateKey> {
+    let mut duplicates = vec![];
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    let seed = ValueSeed {
+        path: String::new(),
+        duplicates: &mut duplicates,
+    };
+    match seed.deserialize(&mut deserializer) {
+        Ok(_) => duplicates,
+        Err(_) => vec![],
+    }
+}
+
+/// deserializes a value like serde_json does, but collects the duplicate keys on the way
+struct ValueSeed<'a> {
+    path: String,
+    duplicates: &'a mut Vec<DuplicateKey>,
+}
+
+impl<'de> DeserializeSeed<'de> for ValueSeed<'_> {
+    type Value = serde_json::Value;
+
+    fn deserialize<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ValueSeed<'_> {
+    type Value = serde_json::Value;
+
+    fn expecting(
+        &self,
+        f: &mut Formatter,
+    ) -> std::fmt::Result {
+        f.write_str("any JSON value")
+    }
+
+    fn visit_bool<E: Error>(
+        self,
+        v: bool,
+    ) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_i64<E: Error>(
+        self,
+        v: i64,
+    ) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_u64<E: Error>(
+        self,
+        v: u64,
+    ) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_f64<E: Error>(
+        self,
+        v: f64,
+    ) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_str<E: Error>(
+        self,
+        v: &str,
+    ) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> { Ok(serde_json::Value::Null) }
+
+    fn visit_seq<A: SeqAccess<'de>>(
+        self,
+        mut seq: A,
+    ) -> Result<Self::Value, A::Error> {
+        let mut items = vec![];
+        while let Some(item) = seq.next_element_seed(ValueSeed {
+            path: format!("{}[{}]", self.path, items.len()),
+            duplicates: &mut *self.duplicates,
+        })? {
+            items.push(item);
+        }
+        Ok(serde_json::Value::Array(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(
+        self,
+        mut map: A,
+    ) -> Result<Self::Value, A::Error> {
+        let child_path = |key: &str| match self.path.is_empty() {
+            true => key.to_string(),
+            false => format!("{}.{key}", self.path),
+        };
+
+        let first_nested = self.duplicates.len();
+        let mut entries: Vec<(String, serde_json::Value)> = vec![];
+        while let Some(key) = map.next_key::<String>()? {
+            let value = map.next_value_seed(ValueSeed {
+                path: child_path(&key),
+                duplicates: &mut *self.duplicates,
+            })?;
+            entries.push((key, value));
+        }
+
+        // in the order of the first occurrences – in front of those of the nested objects
+        let mut own = vec![];
+        for (i, (key, _)) in entries.iter().enumerate() {
+            if entries[..i].iter().any(|(k, _)| k == key) {
+                continue;
+            }
+            let values: Vec<String> = entries.iter().filter(|(k, _)| k == key).map(|(_, v)| v.to_string()).collect();
+            if values.len() > 1 {
+                own.push(DuplicateKey {
+                    path: child_path(key),
+                    values,
+                });
+            }
+        }
+        self.duplicates.splice(first_nested..first_nested, own);
+
+        // the last value wins – like in serde_json
+        Ok(serde_json::Value::Object(entries.into_iter().collect()))
+    }
+}
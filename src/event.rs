@@ -1,20 +1,36 @@
+use crate::keys::KeyBindings;
 use crate::model::{Message, Model};
 use anyhow::Context;
 use crossterm::event;
-use crossterm::event::{Event, KeyCode, KeyModifiers};
+use crossterm::event::{Event, KeyCode, KeyModifiers, MouseButton, MouseEventKind};
 use ratatui::prelude::Size;
 use std::time::Duration;
 
-pub fn handle_event(_: &Model) -> anyhow::Result<Option<Message>> {
-    let event_available = event::poll(Duration::from_millis(250)).context("failed to poll event")?;
-
-    if !event_available {
-        return Ok(None);
+/// waits for the next event – up to the poll interval; without one it blocks until an event arrives
+pub fn handle_event(
+    model: &Model,
+    poll_interval: Option<Duration>,
+) -> anyhow::Result<Option<Message>> {
+    if let Some(poll_interval) = poll_interval {
+        let event_available = event::poll(poll_interval).context("failed to poll event")?;
+        if !event_available {
+            return Ok(None);
+        }
     }
 
     let event = event::read().context("failed to read event")?;
     let message = match event {
-        Event::Key(key) if key.kind == event::KeyEventKind::Press => handle_key(key),
+        Event::Key(key) if key.kind == event::KeyEventKind::Press => {
+            let text_input_active = model.has_find_task()
+                || model.has_filter_task()
+                || model.has_export_task()
+                || model.has_open_file_task()
+                || model.has_projection_task()
+                || model.has_histogram_task()
+                || model.has_quit_confirmation();
+            handle_key(key, &model.props.keys, text_input_active)
+        }
+        Event::Mouse(mouse) => handle_mouse(mouse),
         Event::Resize(cols, rows) => handle_resize(cols, rows),
         _ => None,
     };
@@ -22,35 +38,33 @@ pub fn handle_event(_: &Model) -> anyhow::Result<Option<Message>> {
     Ok(message)
 }
 
-fn handle_key(key: event::KeyEvent) -> Option<Message> {
-    Some(match key.modifiers {
-        KeyModifiers::NONE => match key.code {
-            KeyCode::Home => Message::First,
-            KeyCode::End => Message::Last,
-            KeyCode::Up => Message::ScrollUp,
-            KeyCode::Down => Message::ScrollDown,
-            KeyCode::PageUp => Message::PageUp,
-            KeyCode::PageDown => Message::PageDown,
-            KeyCode::Left => Message::ScrollLeft,
-            KeyCode::Right => Message::ScrollRight,
-            KeyCode::Enter => Message::Enter,
-            KeyCode::Esc => Message::Exit,
-            KeyCode::Char('/') => Message::OpenFindTask,
-            KeyCode::Backspace => Message::Backspace,
-            KeyCode::Char(c) => Message::CharacterInput(c),
-            _ => return None,
-        },
-        KeyModifiers::SHIFT => match key.code {
-            KeyCode::Char(c) => Message::CharacterInput(c),
-            _ => return None,
-        },
-        KeyModifiers::CONTROL => match key.code {
-            KeyCode::Char('s') => Message::SaveSettings,
-            KeyCode::Char('f') => Message::OpenFindTask,
-            _ => return None,
-        },
-        _ => return None,
-    })
+/// maps a key to the bound action; plain character keys are typed into the text input (find, filter, export, open file, projection or histogram dialog) instead,
+/// while it is active; any other key is reported as unbound
+fn handle_key(
+    key: event::KeyEvent,
+    key_bindings: &KeyBindings,
+    text_input_active: bool,
+) -> Option<Message> {
+    if let Some((key, action)) = key_bindings.action(key)
+        && !(text_input_active && key.is_plain_char())
+    {
+        return Some(action.message());
+    }
+
+    match (key.modifiers, key.code) {
+        (KeyModifiers::NONE | KeyModifiers::SHIFT, KeyCode::Char(c)) => Some(Message::CharacterInput(c)),
+        (KeyModifiers::NONE, KeyCode::Backspace) => Some(Message::Backspace),
+        _ => Some(Message::UnboundKey),
+    }
+}
+
+fn handle_mouse(mouse: event::MouseEvent) -> Option<Message> {
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => Some(Message::MouseClick { row: mouse.row }),
+        MouseEventKind::ScrollUp => Some(Message::ScrollUp),
+        MouseEventKind::ScrollDown => Some(Message::ScrollDown),
+        _ => None,
+    }
 }
 
 fn handle_resize(
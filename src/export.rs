@@ -0,0 +1,125 @@
+use crate::raw_json_lines::RawJsonLine;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::io::Write;
+use std::str::FromStr;
+
+/// File format of the exported lines (`--export-format`).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ExportFormat {
+    /// one JSON document per line – as they are in the input
+    #[default]
+    JsonLines,
+    /// a single JSON array with the lines as elements
+    JsonArray,
+    /// a header row with the field names and a row per line – with the values of the fields as columns
+    Csv,
+}
+
+impl ExportFormat {
+    /// writes the lines in this format; CSV columns are the given fields – or, if none, all fields of the lines in order of appearance
+    pub fn write<'a>(
+        &self,
+        writer: impl Write,
+        lines: impl Iterator<Item = &'a RawJsonLine> + Clone,
+        fields: &[String],
+    ) -> io::Result<()> {
+        match self {
+            ExportFormat::JsonLines => write_json_lines(writer, lines),
+            ExportFormat::JsonArray => write_json_array(writer, lines),
+            ExportFormat::Csv => write_csv(writer, lines, fields),
+        }
+    }
+}
+
+fn write_json_lines<'a>(
+    mut writer: impl Write,
+    lines: impl Iterator<Item = &'a RawJsonLine>,
+) -> io::Result<()> {
+    for line in lines {
+        writeln!(writer, "{}", line.content)?;
+    }
+    writer.flush()
+}
+
+/// lines, which are no valid JSON, become string elements
+fn write_json_array<'a>(
+    mut writer: impl Write,
+    lines: impl Iterator<Item = &'a RawJsonLine>,
+) -> io::Result<()> {
+    writer.write_all(b"[")?;
+    for (i, line) in lines.enumerate() {
+        writer.write_all(if i == 0 { b"\n" } else { b",\n" })?;
+        match line.json() {
+            // as it is – keeping the order of the keys
+            Ok(_) => writer.write_all(line.content.trim().as_bytes())?,
+            Err(_) => write!(writer, "{}", serde_json::Value::from(line.content.as_str()))?,
+        }
+    }
+    writer.write_all(b"\n]\n")?;
+    writer.flush()
+}
+
+/// string values are written as they are, other values as JSON; missing fields (and lines without a JSON object) leave the cells empty
+fn write_csv<'a>(
+    writer: impl Write,
+    lines: impl Iterator<Item = &'a RawJsonLine> + Clone,
+    fields: &[String],
+) -> io::Result<()> {
+    let mut columns: Vec<&str> = fields.iter().map(String::as_str).collect();
+    if columns.is_empty() {
+        for line in lines.clone() {
+            if let Ok(serde_json::Value::Object(o)) = line.json() {
+                for k in o.keys() {
+                    if !columns.contains(&k.as_str()) {
+                        columns.push(k);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut csv_writer = csv::Writer::from_writer(writer);
+    csv_writer.write_record(&columns)?;
+    for line in lines {
+        let object = match line.json() {
+            Ok(serde_json::Value::Object(o)) => Some(o),
+            _ => None,
+        };
+        let cells = columns.iter().map(|&k| match object.and_then(|o| o.get(k)) {
+            None => String::new(),
+            Some(serde_json::Value::String(s)) => s.clone(),
+            Some(e) => e.to_string(),
+        });
+        csv_writer.write_record(cells)?;
+    }
+    csv_writer.flush()
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json-lines" => Ok(ExportFormat::JsonLines),
+            "json-array" => Ok(ExportFormat::JsonArray),
+            "csv" => Ok(ExportFormat::Csv),
+            _ => Err(format!(
+                "unknown export format '{s}' – expected `json-lines`, `json-array` or `csv`"
+            )),
+        }
+    }
+}
+
+impl Display for ExportFormat {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        f.write_str(match self {
+            ExportFormat::JsonLines => "json-lines",
+            ExportFormat::JsonArray => "json-array",
+            ExportFormat::Csv => "csv",
+        })
+    }
+}
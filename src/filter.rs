@@ -0,0 +1,167 @@
+use crate::raw_json_lines::{RawJsonLine, parse_timestamp};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// A predicate on a field of a JSON line: `key=value`, `key~substring` or a numeric comparison like `key>500`.
+/// For equality, string values are compared as they are, numbers by numeric value and other values by their JSON representation.
+/// A substring is searched in string values as they are and in the JSON representation of other values.
+/// Comparisons (`<`, `<=`, `>`, `>=`) take numbers and numeric strings; other values don't match.
+#[derive(Clone, Debug)]
+pub struct FieldFilter {
+    pub key: String,
+    pub operator: FilterOperator,
+    pub value: String,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FilterOperator {
+    Equals,
+    Contains,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl FilterOperator {
+    fn symbol(&self) -> &'static str {
+        match self {
+            FilterOperator::Equals => "=",
+            FilterOperator::Contains => "~",
+            FilterOperator::Less => "<",
+            FilterOperator::LessOrEqual => "<=",
+            FilterOperator::Greater => ">",
+            FilterOperator::GreaterOrEqual => ">=",
+        }
+    }
+}
+
+impl FieldFilter {
+    pub fn matches(
+        &self,
+        line: &RawJsonLine,
+    ) -> bool {
+        let Ok(serde_json::Value::Object(o)) = line.json() else {
+            return false;
+        };
+        let Some(field_value) = o.get(&self.key) else {
+            return false;
+        };
+
+        match self.operator {
+            FilterOperator::Equals => self.value_equals(field_value),
+            FilterOperator::Contains => match field_value {
+                serde_json::Value::String(s) => s.contains(&self.value),
+                e => e.to_string().contains(&self.value),
+            },
+            _ => self.value_compares(field_value),
+        }
+    }
+
+    fn value_compares(
+        &self,
+        field_value: &serde_json::Value,
+    ) -> bool {
+        let field_number = match field_value {
+            serde_json::Value::Number(n) => n.as_f64(),
+            serde_json::Value::String(s) => s.trim().parse::<f64>().ok(),
+            _ => None,
+        };
+        let (Some(a), Ok(b)) = (field_number, self.value.trim().parse::<f64>()) else {
+            return false;
+        };
+        match self.operator {
+            FilterOperator::Less => a < b,
+            FilterOperator::LessOrEqual => a <= b,
+            FilterOperator::Greater => a > b,
+            FilterOperator::GreaterOrEqual => a >= b,
+            FilterOperator::Equals | FilterOperator::Contains => false,
+        }
+    }
+
+    fn value_equals(
+        &self,
+        field_value: &serde_json::Value,
+    ) -> bool {
+        match field_value {
+            serde_json::Value::String(s) => s == &self.value,
+            serde_json::Value::Number(n) => self.value.parse::<f64>().is_ok_and(|v| n.as_f64() == Some(v)),
+            serde_json::Value::Bool(b) => self.value.parse::<bool>() == Ok(*b),
+            serde_json::Value::Null => self.value == "null",
+            e => serde_json::from_str::<serde_json::Value>(&self.value).is_ok_and(|v| &v == e),
+        }
+    }
+}
+
+/// A time window on the timestamp in `field` (`--from` / `--to`, both inclusive); lines without a timestamp are outside.
+#[derive(Clone, Debug)]
+pub struct TimeRange {
+    pub field: String,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl TimeRange {
+    pub fn matches(
+        &self,
+        line: &RawJsonLine,
+    ) -> bool {
+        line.timestamp_at(&self.field)
+            .is_some_and(|e| self.from.is_none_or(|from| e >= from) && self.to.is_none_or(|to| e <= to))
+    }
+}
+
+/// parses a time range bound – an ISO-8601 timestamp, a date (UTC midnight)
+/// or a number of seconds (or milliseconds) since the epoch
+pub fn parse_time_bound(s: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(date) = s.parse::<NaiveDate>() {
+        return Ok(date.and_time(NaiveTime::MIN).and_utc());
+    }
+    let value = match serde_json::from_str::<serde_json::Value>(s) {
+        Ok(e @ serde_json::Value::Number(_)) => e,
+        _ => serde_json::Value::String(s.to_string()),
+    };
+    parse_timestamp(&value).ok_or_else(|| format!("invalid timestamp '{s}'"))
+}
+
+impl FromStr for FieldFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some(pos) = s.find(['=', '~', '<', '>']) else {
+            return Err(format!("expected `key=value`, `key~substring` or `key>number`, got '{s}'"));
+        };
+        let key = &s[..pos];
+        if key.is_empty() {
+            return Err(format!("missing key in '{s}'"));
+        }
+        let operator = match (&s[pos..pos + 1], s[pos + 1..].starts_with('=')) {
+            ("~", _) => FilterOperator::Contains,
+            ("<", true) => FilterOperator::LessOrEqual,
+            ("<", false) => FilterOperator::Less,
+            (">", true) => FilterOperator::GreaterOrEqual,
+            (">", false) => FilterOperator::Greater,
+            _ => FilterOperator::Equals,
+        };
+        let value = &s[pos + operator.symbol().len()..];
+        if !matches!(operator, FilterOperator::Equals | FilterOperator::Contains) && value.trim().parse::<f64>().is_err() {
+            return Err(format!("expected a number after '{}' in '{s}'", operator.symbol()));
+        }
+
+        Ok(FieldFilter {
+            key: key.to_string(),
+            operator,
+            value: value.to_string(),
+        })
+    }
+}
+
+impl Display for FieldFilter {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(f, "{}{}{}", self.key, self.operator.symbol(), self.value)
+    }
+}
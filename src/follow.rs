@@ -0,0 +1,115 @@
+use crate::raw_json_lines::{RawJsonLines, SourceName};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+use std::{fs, thread};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// a line appended to a followed file
+pub struct FollowedLine {
+    pub source_name: SourceName,
+    pub line_nr: usize,
+    pub content: String,
+}
+
+/// Watches the given JSON line files for appended lines (like `tail -f`).
+/// Each file is polled by a background thread, which delivers the lines behind those already loaded.
+/// Compressed files, archives and files with a single JSON array are not followed.
+pub fn follow_files(
+    files: &[PathBuf],
+    raw_lines: &RawJsonLines,
+    sender: &Sender<FollowedLine>,
+) {
+    for path in files {
+        // a download can't be followed
+        if crate::load::is_url(path) {
+            continue;
+        }
+        let Some(file_name) = path.file_name().map(|e| e.to_string_lossy().to_string()) else {
+            continue;
+        };
+        if !crate::load::is_json_lines_file_name(&file_name) {
+            continue;
+        }
+
+        if is_json_array_file(path) {
+            continue;
+        }
+
+        let source_name = SourceName::JsonFile(file_name);
+        let last_loaded_line_nr = raw_lines.last_line_nr_of(&source_name);
+        let path = path.clone();
+        let sender = sender.clone();
+        thread::spawn(move || follow_file(&path, source_name, last_loaded_line_nr, sender));
+    }
+}
+
+/// Reads the lines written to a named pipe (FIFO) by a background thread – like a followed file without loaded lines.
+/// Opening the pipe waits for a writer; writers may come and go.
+pub fn read_fifo(
+    path: &Path,
+    sender: &Sender<FollowedLine>,
+) {
+    let Some(file_name) = path.file_name().map(|e| e.to_string_lossy().to_string()) else {
+        return;
+    };
+    let (path, sender) = (path.to_path_buf(), sender.clone());
+    thread::spawn(move || follow_file(&path, SourceName::JsonFile(file_name), 0, sender));
+}
+
+/// a named pipe (FIFO) never ends – so it can't be loaded up front, but is read like a followed file
+pub fn is_fifo(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::FileTypeExt;
+        fs::metadata(path).is_ok_and(|e| e.file_type().is_fifo())
+    }
+    #[cfg(not(unix))]
+    {
+        false
+    }
+}
+
+fn is_json_array_file(path: &Path) -> bool {
+    File::open(path).is_ok_and(|f| crate::load::is_json_array(&mut BufReader::new(f)).unwrap_or(false))
+}
+
+fn follow_file(
+    path: &Path,
+    source_name: SourceName,
+    last_loaded_line_nr: usize,
+    sender: Sender<FollowedLine>,
+) {
+    let Ok(file) = File::open(path) else {
+        return;
+    };
+    let mut reader = BufReader::new(file);
+    let mut line_nr = 0;
+    let mut buf = vec![];
+
+    loop {
+        match reader.read_until(b'\n', &mut buf) {
+            Err(_) => return,
+            // end of file or a partially written line (kept in the buffer) – wait for more
+            Ok(0) => thread::sleep(POLL_INTERVAL),
+            Ok(_) if !buf.ends_with(b"\n") => thread::sleep(POLL_INTERVAL),
+            Ok(_) => {
+                line_nr += 1;
+                if line_nr > last_loaded_line_nr {
+                    let line = FollowedLine {
+                        source_name: source_name.clone(),
+                        line_nr,
+                        content: String::from_utf8_lossy(&buf).trim_end_matches(['\n', '\r']).to_string(),
+                    };
+                    if sender.send(line).is_err() {
+                        return; // receiver is gone
+                    }
+                }
+                buf.clear();
+            }
+        }
+    }
+}
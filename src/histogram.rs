@@ -0,0 +1,46 @@
+use crate::raw_json_lines::RawJsonLine;
+use rustc_hash::FxHashMap;
+
+/// Number of lines per distinct value of a top level field.
+pub struct Histogram {
+    pub field: String,
+    /// distinct values with their number of lines; most frequent first.
+    /// Values are rendered like in a `key=value` filter: strings as they are, other values as JSON.
+    pub buckets: Vec<(String, usize)>,
+    /// number of lines counted (not all of them have the field)
+    pub num_lines: usize,
+}
+
+impl Histogram {
+    pub fn tally(
+        field: &str,
+        lines: &[RawJsonLine],
+    ) -> Self {
+        let mut counts: FxHashMap<String, usize> = FxHashMap::default();
+        for line in lines {
+            let Ok(serde_json::Value::Object(o)) = line.json() else {
+                continue;
+            };
+            let value = match o.get(field) {
+                Some(serde_json::Value::String(s)) => s.clone(),
+                Some(e) => e.to_string(),
+                None => continue,
+            };
+            *counts.entry(value).or_default() += 1;
+        }
+
+        let mut buckets: Vec<(String, usize)> = counts.into_iter().collect();
+        buckets.sort_unstable_by(|(a_value, a_count), (b_value, b_count)| b_count.cmp(a_count).then_with(|| a_value.cmp(b_value)));
+
+        Histogram {
+            field: field.to_string(),
+            buckets,
+            num_lines: lines.len(),
+        }
+    }
+
+    /// number of lines having the field
+    pub fn num_counted_lines(&self) -> usize { self.buckets.iter().map(|(_, count)| count).sum() }
+
+    pub fn max_count(&self) -> usize { self.buckets.first().map_or(0, |(_, count)| *count) }
+}
@@ -0,0 +1,342 @@
+use crate::model::Message;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Program actions, which can be bound to keys in the `[keys]` section of the config file.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    First,
+    Last,
+    ScrollUp,
+    ScrollDown,
+    PageUp,
+    PageDown,
+    HalfPageUp,
+    HalfPageDown,
+    PreviousSource,
+    NextSource,
+    NextTab,
+    ScrollLeft,
+    ScrollRight,
+    Enter,
+    Exit,
+    Find,
+    ToggleCaseInsensitive,
+    ToggleRegexMode,
+    ToggleFuzzyMode,
+    ToggleRenderedMode,
+    ToggleHighlightOnly,
+    Filter,
+    CycleSort,
+    CyclePreset,
+    Export,
+    OpenFile,
+    AppendFile,
+    Reload,
+    Projection,
+    Histogram,
+    CopyValue,
+    SaveSettings,
+    Help,
+}
+
+impl Action {
+    const ALL: [Action; 33] = [
+        Action::First,
+        Action::Last,
+        Action::ScrollUp,
+        Action::ScrollDown,
+        Action::PageUp,
+        Action::PageDown,
+        Action::HalfPageUp,
+        Action::HalfPageDown,
+        Action::PreviousSource,
+        Action::NextSource,
+        Action::NextTab,
+        Action::ScrollLeft,
+        Action::ScrollRight,
+        Action::Enter,
+        Action::Exit,
+        Action::Find,
+        Action::ToggleCaseInsensitive,
+        Action::ToggleRegexMode,
+        Action::ToggleFuzzyMode,
+        Action::ToggleRenderedMode,
+        Action::ToggleHighlightOnly,
+        Action::Filter,
+        Action::CycleSort,
+        Action::CyclePreset,
+        Action::Export,
+        Action::OpenFile,
+        Action::AppendFile,
+        Action::Reload,
+        Action::Projection,
+        Action::Histogram,
+        Action::CopyValue,
+        Action::SaveSettings,
+        Action::Help,
+    ];
+
+    pub fn message(self) -> Message {
+        match self {
+            Action::First => Message::First,
+            Action::Last => Message::Last,
+            Action::ScrollUp => Message::ScrollUp,
+            Action::ScrollDown => Message::ScrollDown,
+            Action::PageUp => Message::PageUp,
+            Action::PageDown => Message::PageDown,
+            Action::HalfPageUp => Message::HalfPageUp,
+            Action::HalfPageDown => Message::HalfPageDown,
+            Action::PreviousSource => Message::PreviousSource,
+            Action::NextSource => Message::NextSource,
+            Action::NextTab => Message::NextTab,
+            Action::ScrollLeft => Message::ScrollLeft,
+            Action::ScrollRight => Message::ScrollRight,
+            Action::Enter => Message::Enter,
+            Action::Exit => Message::Exit,
+            Action::Find => Message::OpenFindTask,
+            Action::ToggleCaseInsensitive => Message::ToggleFindCaseInsensitive,
+            Action::ToggleRegexMode => Message::ToggleFindRegexMode,
+            Action::ToggleFuzzyMode => Message::ToggleFindFuzzyMode,
+            Action::ToggleRenderedMode => Message::ToggleFindRenderedMode,
+            Action::ToggleHighlightOnly => Message::ToggleFindHighlightOnly,
+            Action::Filter => Message::OpenFilterTask,
+            Action::CycleSort => Message::CycleSort,
+            Action::CyclePreset => Message::CyclePreset,
+            Action::Export => Message::OpenExportTask,
+            Action::OpenFile => Message::OpenFileTask { append: false },
+            Action::AppendFile => Message::OpenFileTask { append: true },
+            Action::Reload => Message::Reload,
+            Action::Projection => Message::OpenProjectionTask,
+            Action::Histogram => Message::OpenHistogramTask,
+            Action::CopyValue => Message::CopyValue,
+            Action::SaveSettings => Message::SaveSettings,
+            Action::Help => Message::Help,
+        }
+    }
+
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Action::First => &["Home", "g"],
+            Action::Last => &["End", "G"],
+            Action::ScrollUp => &["Up", "k"],
+            Action::ScrollDown => &["Down", "j"],
+            Action::PageUp => &["PageUp"],
+            Action::PageDown => &["PageDown"],
+            Action::HalfPageUp => &["Ctrl-u"],
+            Action::HalfPageDown => &["Ctrl-d"],
+            Action::PreviousSource => &["Ctrl-PageUp"],
+            Action::NextSource => &["Ctrl-PageDown"],
+            // not `Tab` – that's `Ctrl-i` in most terminals
+            Action::NextTab => &["Alt-t"],
+            Action::ScrollLeft => &["Left", "h"],
+            Action::ScrollRight => &["Right", "l"],
+            Action::Enter => &["Enter"],
+            Action::Exit => &["Esc"],
+            Action::Find => &["Ctrl-f", "/"],
+            // most terminals send `Ctrl-i` as Tab
+            Action::ToggleCaseInsensitive => &["Ctrl-i", "Tab"],
+            Action::ToggleRegexMode => &["Ctrl-r"],
+            Action::ToggleFuzzyMode => &["Ctrl-z"],
+            Action::ToggleRenderedMode => &["Ctrl-v"],
+            Action::ToggleHighlightOnly => &["Ctrl-t"],
+            Action::Filter => &["Ctrl-l"],
+            Action::CycleSort => &["Ctrl-o"],
+            Action::CyclePreset => &["Alt-p"],
+            Action::Export => &["Ctrl-e"],
+            Action::OpenFile => &["Ctrl-n"],
+            Action::AppendFile => &["Alt-n"],
+            Action::Reload => &["F5"],
+            Action::Projection => &["Ctrl-p"],
+            Action::Histogram => &["Ctrl-g"],
+            Action::CopyValue => &["Ctrl-y"],
+            Action::SaveSettings => &["Ctrl-s"],
+            Action::Help => &["F1", "?"],
+        }
+    }
+}
+
+impl Display for Action {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        let name = serde_json::to_value(self)
+            .ok()
+            .and_then(|e| e.as_str().map(str::to_string))
+            .unwrap_or_default();
+        f.pad(&name)
+    }
+}
+
+/// A key with modifiers, written like `Ctrl-q`, `Alt-Down`, `F1`, `Esc` or `j`.
+/// The Shift modifier is part of the character for character keys (`G` instead of `Shift-g`).
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Key {
+    fn new(
+        code: KeyCode,
+        mut modifiers: KeyModifiers,
+    ) -> Self {
+        if let KeyCode::Char(_) = code {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        Key { code, modifiers }
+    }
+
+    /// true for keys producing a character without modifiers – these are typed into a text input instead
+    pub fn is_plain_char(&self) -> bool { matches!(self.code, KeyCode::Char(_)) && self.modifiers.is_empty() }
+}
+
+impl From<KeyEvent> for Key {
+    fn from(e: KeyEvent) -> Self { Key::new(e.code, e.modifiers) }
+}
+
+impl FromStr for Key {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s;
+        // a single `-` is a key itself
+        while let Some((modifier, tail)) = rest.split_once('-').filter(|(_, tail)| !tail.is_empty()) {
+            modifiers |= match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(format!("invalid key modifier '{modifier}' in key '{s}'")),
+            };
+            rest = tail;
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::Char(c.to_ascii_uppercase()),
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "enter" => KeyCode::Enter,
+                "esc" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backspace" => KeyCode::Backspace,
+                "delete" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "space" => KeyCode::Char(' '),
+                f => match f.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n) => KeyCode::F(n),
+                    None => return Err(format!("invalid key '{s}'")),
+                },
+            },
+        };
+
+        Ok(Key::new(code, modifiers))
+    }
+}
+
+impl TryFrom<String> for Key {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> { s.parse() }
+}
+
+impl From<Key> for String {
+    fn from(key: Key) -> Self { key.to_string() }
+}
+
+impl Display for Key {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            f.write_str("Ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            f.write_str("Alt-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            f.write_str("Shift-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("Space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::F(n) => write!(f, "F{n}"),
+            KeyCode::PageUp => f.write_str("PageUp"),
+            KeyCode::PageDown => f.write_str("PageDown"),
+            code => write!(f, "{code}"),
+        }
+    }
+}
+
+/// Keys bound to the actions.
+/// Actions configured in the `[keys]` section get the configured keys instead of their default keys, e.g. `exit = ["Esc", "Ctrl-q"]`.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(from = "BTreeMap<Action, Vec<Key>>", into = "BTreeMap<Action, Vec<Key>>")]
+pub struct KeyBindings {
+    keys: BTreeMap<Action, Vec<Key>>,
+    actions: FxHashMap<Key, Action>,
+}
+
+impl KeyBindings {
+    /// returns the action bound to the key
+    pub fn action(
+        &self,
+        key: KeyEvent,
+    ) -> Option<(Key, Action)> {
+        let key = Key::from(key);
+        self.actions.get(&key).map(|&action| (key, action))
+    }
+
+    /// actions with their keys in a stable order
+    pub fn iter(&self) -> impl Iterator<Item = (&Action, &Vec<Key>)> { self.keys.iter() }
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self { KeyBindings::from(BTreeMap::new()) }
+}
+
+impl From<BTreeMap<Action, Vec<Key>>> for KeyBindings {
+    fn from(configured: BTreeMap<Action, Vec<Key>>) -> Self {
+        let mut keys: BTreeMap<Action, Vec<Key>> = Action::ALL
+            .iter()
+            .map(|&action| {
+                (
+                    action,
+                    action
+                        .default_keys()
+                        .iter()
+                        .map(|e| e.parse().expect("default key should be valid"))
+                        .collect(),
+                )
+            })
+            .collect();
+        keys.extend(configured);
+
+        let actions = keys
+            .iter()
+            .flat_map(|(&action, keys)| keys.iter().map(move |&key| (key, action)))
+            .collect();
+        KeyBindings { keys, actions }
+    }
+}
+
+impl From<KeyBindings> for BTreeMap<Action, Vec<Key>> {
+    fn from(bindings: KeyBindings) -> Self { bindings.keys }
+}
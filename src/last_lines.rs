@@ -0,0 +1,110 @@
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::{fs, path};
+
+const MAX_FILES: usize = 100;
+
+/// the last selected lines of recently viewed files; most recent first
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct State {
+    files: Vec<FileLine>,
+    /// search strings of the find dialog; oldest first
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    searches: Vec<String>,
+}
+
+impl State {
+    /// reads the state file; a missing or broken one is just an empty state
+    fn read(state_file: &Path) -> Self {
+        fs::read_to_string(state_file)
+            .ok()
+            .and_then(|e| toml::from_str::<State>(&e).ok())
+            .unwrap_or_default()
+    }
+
+    fn write(
+        &self,
+        state_file: &Path,
+    ) -> anyhow::Result<()> {
+        let toml = toml::to_string_pretty(self)?;
+        fs::write(state_file, toml).with_context(|| format!("failed to write state file {state_file:?}"))
+    }
+}
+
+/// the search strings of the find dialog in earlier sessions; oldest first
+pub fn find_history(state_file: &Path) -> Vec<String> { State::read(state_file).searches }
+
+/// remembers the search strings of the find dialog for the next sessions
+pub fn save_find_history(
+    state_file: &Path,
+    searches: &[String],
+) -> anyhow::Result<()> {
+    let mut state = State::read(state_file);
+    state.searches = searches.to_vec();
+    state.write(state_file)
+}
+
+#[derive(Serialize, Deserialize)]
+struct FileLine {
+    path: PathBuf,
+    /// file size, when the line was selected; a smaller file is not the same one anymore (e.g. rotated)
+    size: u64,
+    /// index of the selected line in the file
+    line: usize,
+    /// indices of the bookmarked lines
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    bookmarks: Vec<usize>,
+}
+
+/// Remembers the last selected line (and the bookmarks) of an input file across sessions – in a state file next to the config file.
+pub struct LastLine {
+    state_file: PathBuf,
+    path: PathBuf,
+    size: u64,
+    state: State,
+}
+
+impl LastLine {
+    /// reads the state file; a missing or broken one is just an empty state
+    pub fn open(
+        state_file: PathBuf,
+        file: &Path,
+    ) -> Self {
+        let state = State::read(&state_file);
+        LastLine {
+            state_file,
+            path: path::absolute(file).unwrap_or_else(|_| file.to_path_buf()),
+            size: fs::metadata(file).map(|e| e.len()).unwrap_or_default(),
+            state,
+        }
+    }
+
+    /// index of the line selected last time in this file
+    pub fn get(&self) -> Option<usize> { self.file_line().map(|e| e.line) }
+
+    /// indices of the lines bookmarked last time in this file
+    pub fn bookmarks(&self) -> Vec<usize> { self.file_line().map(|e| e.bookmarks.clone()).unwrap_or_default() }
+
+    fn file_line(&self) -> Option<&FileLine> { self.state.files.iter().find(|e| e.path == self.path && e.size <= self.size) }
+
+    pub fn save(
+        mut self,
+        line: usize,
+        bookmarks: Vec<usize>,
+    ) -> anyhow::Result<()> {
+        self.state.files.retain(|e| e.path != self.path);
+        self.state.files.insert(
+            0,
+            FileLine {
+                path: self.path,
+                size: self.size,
+                line,
+                bookmarks,
+            },
+        );
+        self.state.files.truncate(MAX_FILES);
+        self.state.write(&self.state_file)
+    }
+}
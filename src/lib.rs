@@ -0,0 +1,24 @@
+//! JSON Lines Viewer – the loading, filtering and rendering engine behind the terminal UI;
+//! the `Model` can be driven headless with `Model::step` (e.g. in tests or other tools)
+#![feature(iter_advance_by)]
+pub mod clipboard;
+pub mod duplicate_keys;
+pub mod event;
+pub mod export;
+pub mod filter;
+pub mod follow;
+pub mod histogram;
+pub mod keys;
+pub mod last_lines;
+pub mod load;
+pub mod model;
+pub mod progress;
+pub mod props;
+pub mod raw_json_lines;
+pub mod recent_sessions;
+pub mod sort;
+pub mod terminal;
+pub mod theme;
+
+pub use load::load_json_lines;
+pub use model::{Message, Model};
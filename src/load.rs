@@ -0,0 +1,610 @@
+use crate::progress::LoadProgress;
+use crate::raw_json_lines::{RawJsonLines, SourceName};
+use anyhow::Context;
+use bzip2::read::MultiBzDecoder;
+use flate2::read::MultiGzDecoder;
+use serde::de::{IgnoredAny, SeqAccess, Visitor};
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{BufRead, Seek};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::{fmt, io, thread};
+use xz2::read::XzDecoder;
+use xz2::stream::Stream;
+
+pub fn is_json_lines_file_name(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    [".json", ".jsonl", ".ndjson"].iter().any(|e| name.ends_with(e))
+}
+
+/// true for an `http://` or `https://` URL given instead of a file path
+pub fn is_url(path: &Path) -> bool { path.to_str().is_some_and(|e| e.starts_with("http://") || e.starts_with("https://")) }
+
+/// The lines read from a file (or stdin) – up to the line limit (`--max-lines`) or just the last ones (`--tail`).
+pub struct LoadedLines {
+    raw_lines: RawJsonLines,
+    line_limit: Option<SharedLineLimit>,
+    tail: Option<usize>,
+    /// lines dropped in front of the last ones
+    num_dropped_lines: usize,
+    /// the line limit was reached before the end of the file
+    truncated: bool,
+}
+
+/// The line limit (`--max-lines`) of files loaded together – the files take their lines in argument order,
+/// so a file may load only what the earlier files leave over.
+#[derive(Clone)]
+struct SharedLineLimit {
+    max_lines: usize,
+    /// lines loaded so far – per file
+    num_loaded: Arc<[AtomicUsize]>,
+    /// position of the file among the files loaded together
+    file_pos: usize,
+}
+
+impl SharedLineLimit {
+    fn new(
+        max_lines: usize,
+        num_files: usize,
+    ) -> Self {
+        SharedLineLimit {
+            max_lines,
+            num_loaded: (0..num_files).map(|_| AtomicUsize::new(0)).collect(),
+            file_pos: 0,
+        }
+    }
+
+    fn for_file(
+        &self,
+        file_pos: usize,
+    ) -> Self {
+        SharedLineLimit { file_pos, ..self.clone() }
+    }
+
+    /// Counts a line of the file, if the file and the earlier ones together stay within the limit.
+    /// The earlier files may still be loading, so their lines only ever grow – a line refused here would be dropped anyway.
+    fn reserve_line(&self) -> bool {
+        let num_lines: usize = self.num_loaded[..=self.file_pos].iter().map(|e| e.load(Ordering::Relaxed)).sum();
+        if num_lines >= self.max_lines {
+            return false;
+        }
+        self.num_loaded[self.file_pos].fetch_add(1, Ordering::Relaxed);
+        true
+    }
+}
+
+impl LoadedLines {
+    pub fn new(
+        max_lines: Option<usize>,
+        tail: Option<usize>,
+    ) -> Self {
+        Self::sharing_line_limit(max_lines.map(|e| SharedLineLimit::new(e, 1)), tail)
+    }
+
+    /// lines of a file loaded along with other files – see [`SharedLineLimit`]
+    fn sharing_line_limit(
+        line_limit: Option<SharedLineLimit>,
+        tail: Option<usize>,
+    ) -> Self {
+        LoadedLines {
+            raw_lines: RawJsonLines::default(),
+            line_limit,
+            tail,
+            num_dropped_lines: 0,
+            truncated: false,
+        }
+    }
+
+    /// reserves a line for the next `push`; false, if the line limit (`--max-lines`) is reached
+    pub fn reserve_line(&mut self) -> bool {
+        let reserved = self.line_limit.as_ref().is_none_or(|e| e.reserve_line());
+        self.truncated |= !reserved;
+        reserved
+    }
+
+    pub fn push(
+        &mut self,
+        source_name: SourceName,
+        line_nr: usize,
+        content: String,
+    ) {
+        self.raw_lines.push(source_name, line_nr, content);
+        // the earlier lines are dropped in batches, so they aren't shifted on every line, while the memory usage
+        // stays bounded (at twice the tail)
+        if let Some(tail) = self.tail
+            && self.raw_lines.lines.len() >= 2 * tail.max(1)
+        {
+            self.drop_lines_before_tail(tail);
+        }
+    }
+
+    /// the loaded lines and the number of lines dropped in front of them
+    pub fn finish(mut self) -> (RawJsonLines, usize) {
+        if let Some(tail) = self.tail {
+            self.drop_lines_before_tail(tail);
+        }
+        (self.raw_lines, self.num_dropped_lines)
+    }
+
+    /// true, if the line limit cut the lines off
+    fn is_truncated(&self) -> bool { self.truncated }
+
+    fn drop_lines_before_tail(
+        &mut self,
+        tail: usize,
+    ) {
+        let num_dropped = self.raw_lines.lines.len().saturating_sub(tail);
+        self.raw_lines.lines.drain(..num_dropped);
+        self.num_dropped_lines += num_dropped;
+    }
+}
+
+/// loads the files in parallel – one thread per file – and joins their lines in the given order;
+/// the files share the line limit in that order, so once it is reached, the later files are truncated
+pub fn load_files(
+    files: &[PathBuf],
+    max_lines: Option<usize>,
+    tail: Option<usize>,
+    csv_numbers: bool,
+    progress: &LoadProgress,
+) -> anyhow::Result<RawJsonLines> {
+    let line_limit = max_lines.map(|e| SharedLineLimit::new(e, files.len()));
+    let loaded_files: Vec<anyhow::Result<(RawJsonLines, bool)>> = thread::scope(|s| {
+        let threads: Vec<_> = files
+            .iter()
+            .enumerate()
+            .map(|(file_pos, path)| {
+                let lines = LoadedLines::sharing_line_limit(line_limit.as_ref().map(|e| e.for_file(file_pos)), tail);
+                s.spawn(move || {
+                    let file_lines = load_file_into(lines, path, csv_numbers, progress);
+                    progress.file_loaded();
+                    file_lines
+                })
+            })
+            .collect();
+        threads
+            .into_iter()
+            .map(|e| e.join().expect("file loading thread should not panic"))
+            .collect()
+    });
+
+    // a file may have loaded lines, before the earlier files took them
+    let mut raw_lines = RawJsonLines::default();
+    for (path, file_lines) in files.iter().zip(loaded_files) {
+        let (mut file_lines, truncated) = file_lines?;
+        let remaining_lines = max_lines.map_or(usize::MAX, |e| e.saturating_sub(raw_lines.lines.len()));
+        if file_lines.lines.len() > remaining_lines {
+            if !truncated {
+                progress.note(format!("'{}' truncated – line limit reached", path.to_string_lossy()));
+            }
+            file_lines.lines.truncate(remaining_lines);
+        }
+        raw_lines.append(file_lines);
+    }
+
+    Ok(raw_lines)
+}
+
+/// loads the lines of a single file (up to the line limit or just the last `tail` lines);
+/// `csv_numbers` takes numeric values of CSV/TSV files as JSON numbers
+pub fn load_file(
+    path: &Path,
+    max_lines: Option<usize>,
+    tail: Option<usize>,
+    csv_numbers: bool,
+    progress: &LoadProgress,
+) -> anyhow::Result<RawJsonLines> {
+    let (raw_lines, _) = load_file_into(LoadedLines::new(max_lines, tail), path, csv_numbers, progress)?;
+    Ok(raw_lines)
+}
+
+/// loads the lines of a single file into `lines`, which may share the line limit with other files;
+/// also tells, whether the line limit truncated the file
+fn load_file_into(
+    mut lines: LoadedLines,
+    path: &Path,
+    csv_numbers: bool,
+    progress: &LoadProgress,
+) -> anyhow::Result<(RawJsonLines, bool)> {
+    match path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()).as_deref() {
+        _ if is_url(path) => load_lines_from_url(&mut lines, path, progress)
+            .with_context(|| format!("failed to load lines from {}", path.to_string_lossy()))?,
+        Some("json" | "jsonl" | "ndjson") => {
+            load_lines_from_json(&mut lines, path, progress).with_context(|| format!("failed to load lines from {path:?}"))?
+        }
+        Some("gz") => load_lines_from_compressed(&mut lines, path, |f| Ok(MultiGzDecoder::new(f)), progress)
+            .with_context(|| format!("failed to load lines from {path:?}"))?,
+        Some("bz2") => load_lines_from_compressed(&mut lines, path, |f| Ok(MultiBzDecoder::new(f)), progress)
+            .with_context(|| format!("failed to load lines from {path:?}"))?,
+        Some("xz") => load_lines_from_compressed(&mut lines, path, |f| Ok(XzDecoder::new_multi_decoder(f)), progress)
+            .with_context(|| format!("failed to load lines from {path:?}"))?,
+        Some("lzma") => load_lines_from_compressed(
+            &mut lines,
+            path,
+            |f| Ok(XzDecoder::new_stream(f, Stream::new_lzma_decoder(u64::MAX)?)),
+            progress,
+        )
+        .with_context(|| format!("failed to load lines from {path:?}"))?,
+        Some("csv") => load_lines_from_csv(&mut lines, path, b',', csv_numbers, progress)
+            .with_context(|| format!("failed to load lines from {path:?}"))?,
+        Some("tsv") => load_lines_from_csv(&mut lines, path, b'\t', csv_numbers, progress)
+            .with_context(|| format!("failed to load lines from {path:?}"))?,
+        Some("zip") => load_lines_from_zip(&mut lines, path, progress).with_context(|| format!("failed to load lines from {path:?}"))?,
+        _ => progress.note(format!("unknown file extension: '{}'", path.to_string_lossy())),
+    }
+    let truncated = lines.is_truncated();
+    let (raw_lines, num_dropped_lines) = lines.finish();
+    if num_dropped_lines > 0 {
+        progress.note(format!(
+            "'{}': {num_dropped_lines} earlier lines dropped – keeping the last ones only",
+            path.to_string_lossy()
+        ));
+    }
+    Ok((raw_lines, truncated))
+}
+
+/// loads the JSON lines read from `reader` (e.g. for embedding the viewer), named after `source_name`
+pub fn load_json_lines(
+    reader: impl io::Read,
+    source_name: &str,
+) -> io::Result<RawJsonLines> {
+    let mut raw_lines = RawJsonLines::default();
+    for (line_nr, line) in lossy_lines(io::BufReader::new(reader)).enumerate() {
+        let (line, _) = line?;
+        raw_lines.push(SourceName::JsonFile(source_name.to_string()), line_nr + 1, line);
+    }
+    Ok(raw_lines)
+}
+
+/// loads a JSON lines file – or a JSON file with a single top level array, whose elements are taken as lines
+/// (numbered by their position in the array)
+fn load_lines_from_json(
+    lines: &mut LoadedLines,
+    path: &Path,
+    progress: &LoadProgress,
+) -> anyhow::Result<()> {
+    let json_file = File::open(path).context("failed to open json")?;
+    let mut json_file = io::BufReader::new(json_file);
+
+    let file_name: String = path
+        .file_name()
+        .context("BUG: json path is missing filename")?
+        .to_string_lossy()
+        .into();
+    if load_json_array_elements(lines, &mut json_file, &SourceName::JsonFile(file_name), path, progress).context("failed to read json")? {
+        return Ok(());
+    }
+
+    let mut num_lossy_lines = 0;
+    for (line_nr, line) in lossy_lines(json_file).enumerate() {
+        let (line, lossy) = line.context("failed to read json line")?;
+        if !lines.reserve_line() {
+            progress.note(format!("'{}' truncated – line limit reached", path.to_string_lossy()));
+            break;
+        }
+        let file_name = path
+            .file_name()
+            .context("BUG: json path is missing filename")?
+            .to_string_lossy()
+            .into();
+        let source_name = SourceName::JsonFile(file_name);
+
+        num_lossy_lines += usize::from(lossy);
+        progress.line_read(line.len());
+        lines.push(source_name, line_nr + 1, line);
+    }
+    note_lossy_lines(path, num_lossy_lines, progress);
+
+    Ok(())
+}
+
+/// true, if the file starts like a JSON array – JSON lines start with an object usually
+fn starts_with_bracket(file: &mut io::BufReader<File>) -> io::Result<bool> {
+    Ok(file.fill_buf()?.iter().find(|e| !e.is_ascii_whitespace()) == Some(&b'['))
+}
+
+/// true, if the file contains a single JSON array (possibly spanning many lines) instead of JSON lines;
+/// the elements are skipped while reading, so they are not kept in memory
+pub fn is_json_array(file: &mut io::BufReader<File>) -> io::Result<bool> {
+    if !starts_with_bracket(file)? {
+        return Ok(false);
+    }
+    let mut deserializer = serde_json::Deserializer::from_reader(file);
+    Ok(serde::Deserializer::deserialize_seq(&mut deserializer, IgnoredAny).is_ok() && deserializer.end().is_ok())
+}
+
+/// loads the elements of a file with a single JSON array (possibly spanning many lines) as lines – numbered by their position
+/// in the array; the whole file is checked to be one before, then the elements are streamed, so that no more of them are read
+/// than the line limit allows.
+/// Returns false for anything else (e.g. JSON lines) – with the file rewound to its start and no lines loaded.
+fn load_json_array_elements(
+    lines: &mut LoadedLines,
+    file: &mut io::BufReader<File>,
+    source_name: &SourceName,
+    path: &Path,
+    progress: &LoadProgress,
+) -> io::Result<bool> {
+    // a JSON lines file may start with an array on its first line, which is followed by more lines
+    let is_array = is_json_array(file)?;
+    file.rewind()?;
+    if !is_array {
+        return Ok(false);
+    }
+
+    let mut visitor = JsonArrayVisitor {
+        lines,
+        source_name,
+        progress,
+        truncated: false,
+    };
+    let mut deserializer = serde_json::Deserializer::from_reader(&mut *file);
+    let result = serde::Deserializer::deserialize_seq(&mut deserializer, &mut visitor);
+    // the elements left out make the array look unfinished to the deserializer
+    if visitor.truncated {
+        progress.note(format!("'{}' truncated – line limit reached", path.to_string_lossy()));
+        return Ok(true);
+    }
+    result?;
+    Ok(true)
+}
+
+/// pushes the elements of a JSON array as lines – until the line limit is reached
+struct JsonArrayVisitor<'a> {
+    lines: &'a mut LoadedLines,
+    source_name: &'a SourceName,
+    progress: &'a LoadProgress,
+    /// elements are left out due to the line limit
+    truncated: bool,
+}
+
+impl<'de> Visitor<'de> for &mut JsonArrayVisitor<'_> {
+    type Value = ();
+
+    fn expecting(
+        &self,
+        f: &mut fmt::Formatter,
+    ) -> fmt::Result {
+        f.write_str("a JSON array")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(
+        self,
+        mut seq: A,
+    ) -> Result<(), A::Error> {
+        let mut line_nr = 0;
+        while let Some(element) = seq.next_element::<serde_json::Value>()? {
+            if !self.lines.reserve_line() {
+                self.truncated = true;
+                break;
+            }
+            let line = element.to_string();
+            line_nr += 1;
+            self.progress.line_read(line.len());
+            self.lines.push(self.source_name.clone(), line_nr, line);
+        }
+        Ok(())
+    }
+}
+
+/// loads a compressed JSON lines file (gzip, bzip2, xz or lzma) through the given decoder;
+/// the source name is the filename without the compression extension (e.g. `events.json.gz` becomes `events.json`)
+fn load_lines_from_compressed<R: io::Read>(
+    lines: &mut LoadedLines,
+    path: &Path,
+    decoder: impl FnOnce(File) -> io::Result<R>,
+    progress: &LoadProgress,
+) -> anyhow::Result<()> {
+    let compressed_file = File::open(path).context("failed to open compressed file")?;
+    let decompressed_file = io::BufReader::new(decoder(compressed_file).context("failed to initialize decoder")?);
+
+    let mut num_lossy_lines = 0;
+    for (line_nr, line) in lossy_lines(decompressed_file).enumerate() {
+        let (line, lossy) = line.context("failed to read decompressed line")?;
+        if !lines.reserve_line() {
+            progress.note(format!("'{}' truncated – line limit reached", path.to_string_lossy()));
+            break;
+        }
+        let file_name = path
+            .file_stem()
+            .context("BUG: compressed file path is missing filename")?
+            .to_string_lossy()
+            .into();
+        let source_name = SourceName::JsonFile(file_name);
+
+        num_lossy_lines += usize::from(lossy);
+        progress.line_read(line.len());
+        lines.push(source_name, line_nr + 1, line);
+    }
+    note_lossy_lines(path, num_lossy_lines, progress);
+
+    Ok(())
+}
+
+/// loads the JSON lines of an `http(s)` response body – streamed, so the progress shows while downloading;
+/// the source name is the host with the URL path (e.g. `https://host/logs/app.jsonl?day=3` becomes `host/logs/app.jsonl`),
+/// so that equally named files of different URLs are separate sources
+fn load_lines_from_url(
+    lines: &mut LoadedLines,
+    url: &Path,
+    progress: &LoadProgress,
+) -> anyhow::Result<()> {
+    let url = url.to_string_lossy();
+    let response = ureq::get(url.as_ref()).call().context("request failed")?;
+    let body = io::BufReader::new(response.into_body().into_reader());
+
+    let without_scheme = url.split_once("://").map_or(url.as_ref(), |(_, e)| e);
+    let file_name = without_scheme.split(['?', '#']).next().unwrap_or_default().trim_end_matches('/');
+
+    let mut num_lossy_lines = 0;
+    for (line_nr, line) in lossy_lines(body).enumerate() {
+        let (line, lossy) = line.context("failed to read line from response")?;
+        if !lines.reserve_line() {
+            progress.note(format!("'{url}' truncated – line limit reached"));
+            break;
+        }
+        num_lossy_lines += usize::from(lossy);
+        progress.line_read(line.len());
+        lines.push(SourceName::JsonFile(file_name.to_string()), line_nr + 1, line);
+    }
+    note_lossy_lines(Path::new(url.as_ref()), num_lossy_lines, progress);
+
+    Ok(())
+}
+
+/// loads a CSV (or TSV) file with a header row – each data row as a JSON object with the header names as keys
+/// (numbered by its line in the file)
+fn load_lines_from_csv(
+    lines: &mut LoadedLines,
+    path: &Path,
+    delimiter: u8,
+    numbers: bool,
+    progress: &LoadProgress,
+) -> anyhow::Result<()> {
+    let csv_file = File::open(path).context("failed to open csv")?;
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_reader(io::BufReader::new(csv_file));
+    let header: Vec<String> = reader
+        .byte_headers()
+        .context("failed to read header row")?
+        .iter()
+        .map(|e| String::from_utf8_lossy(e).into_owned())
+        .collect();
+    let file_name: String = path
+        .file_name()
+        .context("BUG: csv path is missing filename")?
+        .to_string_lossy()
+        .into();
+
+    let mut num_lossy_lines = 0;
+    for record in reader.byte_records() {
+        let record = record.context("failed to read csv row")?;
+        if !lines.reserve_line() {
+            progress.note(format!("'{}' truncated – line limit reached", path.to_string_lossy()));
+            break;
+        }
+        let mut lossy = false;
+        // keeps the order of the header – serde_json's map would sort the keys
+        let fields: Vec<String> = header
+            .iter()
+            .zip(record.iter())
+            .map(|(key, value)| {
+                let value = String::from_utf8_lossy(value);
+                lossy |= matches!(value, Cow::Owned(_));
+                format!("{}:{}", serde_json::Value::from(key.as_str()), csv_value(&value, numbers))
+            })
+            .collect();
+        let line = format!("{{{}}}", fields.join(","));
+
+        num_lossy_lines += usize::from(lossy);
+        progress.line_read(line.len());
+        let line_nr = record.position().map_or(0, |e| e.line() as usize);
+        lines.push(SourceName::JsonFile(file_name.clone()), line_nr, line);
+    }
+    note_lossy_lines(path, num_lossy_lines, progress);
+
+    Ok(())
+}
+
+/// a CSV value as JSON – a number, if it is one in JSON syntax and `numbers` is set; a string otherwise
+fn csv_value(
+    value: &str,
+    numbers: bool,
+) -> serde_json::Value {
+    match value.parse::<serde_json::Number>() {
+        Ok(number) if numbers => serde_json::Value::Number(number),
+        _ => serde_json::Value::from(value),
+    }
+}
+
+fn load_lines_from_zip(
+    lines: &mut LoadedLines,
+    path: &Path,
+    progress: &LoadProgress,
+) -> anyhow::Result<()> {
+    let zip_file = File::open(path).context("failed to open zip")?;
+    let mut archive = zip::ZipArchive::new(zip_file).context("failed to parse zip")?;
+
+    let mut num_lossy_lines = 0;
+    for i in 0..archive.len() {
+        let f = archive
+            .by_index(i)
+            .with_context(|| format!("failed to get file with index {i} from zip"))?;
+
+        // gzipped JSON files are decompressed and named without the `.gz` extension
+        let entry_name = f.name().to_string();
+        let gzipped_name = f.name().strip_suffix(".gz").or_else(|| f.name().strip_suffix(".GZ"));
+        let (json_file, gzipped) = match gzipped_name {
+            Some(name) => (name.to_string(), true),
+            None => (f.name().to_string(), false),
+        };
+        if !f.is_file() || !is_json_lines_file_name(&json_file) {
+            continue;
+        }
+
+        let f: Box<dyn BufRead> = match gzipped {
+            true => Box::new(io::BufReader::new(MultiGzDecoder::new(f))),
+            false => Box::new(io::BufReader::new(f)),
+        };
+
+        for (line_nr, line) in lossy_lines(f).enumerate() {
+            let (line, lossy) = line.with_context(|| format!("failed to read line from '{entry_name}' in zip"))?;
+            if !lines.reserve_line() {
+                progress.note(format!("'{}' truncated – line limit reached", path.to_string_lossy()));
+                note_lossy_lines(path, num_lossy_lines, progress);
+                return Ok(());
+            }
+            let zip_file = path
+                .file_name()
+                .context("BUG: zip path is missing filename")?
+                .to_string_lossy()
+                .into();
+            let json_file = json_file.clone();
+            let source_name = SourceName::JsonInZip { zip_file, json_file };
+
+            num_lossy_lines += usize::from(lossy);
+            progress.line_read(line.len());
+            lines.push(source_name, line_nr + 1, line);
+        }
+    }
+    note_lossy_lines(path, num_lossy_lines, progress);
+
+    Ok(())
+}
+
+/// like `BufRead::lines()`, but decodes invalid UTF-8 lossily (as `U+FFFD`) instead of failing –
+/// so a single broken byte doesn't prevent loading the rest of a file; the flag tells, if the line was decoded lossily
+pub fn lossy_lines(mut reader: impl BufRead) -> impl Iterator<Item = io::Result<(String, bool)>> {
+    let mut buf = Vec::new();
+    std::iter::from_fn(move || {
+        buf.clear();
+        match reader.read_until(b'\n', &mut buf) {
+            Err(e) => Some(Err(e)),
+            Ok(0) => None,
+            Ok(_) => {
+                let line = buf.strip_suffix(b"\n").unwrap_or(&buf);
+                let line = line.strip_suffix(b"\r").unwrap_or(line);
+                Some(Ok(match String::from_utf8_lossy(line) {
+                    Cow::Borrowed(e) => (e.to_string(), false),
+                    Cow::Owned(e) => (e, true),
+                }))
+            }
+        }
+    })
+}
+
+fn note_lossy_lines(
+    path: &Path,
+    num_lossy_lines: usize,
+    progress: &LoadProgress,
+) {
+    if num_lossy_lines > 0 {
+        progress.note(format!(
+            "'{}' has {num_lossy_lines} lines with invalid UTF-8 – decoded lossily",
+            path.to_string_lossy()
+        ));
+    }
+}
@@ -1,54 +1,307 @@
-#![feature(iter_advance_by)]
-mod event;
-mod model;
-mod props;
-mod raw_json_lines;
-mod terminal;
-
-use crate::model::{Model, Screen};
-use crate::props::Props;
-use crate::raw_json_lines::{RawJsonLines, SourceName};
-use anyhow::{anyhow, Context};
+use anyhow::{Context, anyhow, bail};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Utc};
 use clap::Parser;
-use ratatui::prelude::Backend;
+use json_lines_viewer::export::ExportFormat;
+use json_lines_viewer::filter::{FieldFilter, TimeRange, parse_time_bound};
+use json_lines_viewer::follow::FollowedLine;
+use json_lines_viewer::last_lines::LastLine;
+use json_lines_viewer::load::{LoadedLines, is_url, load_files, lossy_lines};
+use json_lines_viewer::model::{GotoTarget, Model, Screen, TimeFormat, ViewOptions};
+use json_lines_viewer::progress::LoadProgress;
+use json_lines_viewer::props::Props;
+use json_lines_viewer::raw_json_lines::{RawJsonLines, SourceName};
+use json_lines_viewer::sort::LineSort;
+use json_lines_viewer::{event, follow, last_lines, recent_sessions, terminal};
 use ratatui::Terminal;
-use std::fs::File;
-use std::io;
-use std::io::BufRead;
+use ratatui::prelude::{Backend, Size};
+use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, mpsc};
+use std::thread::JoinHandle;
+use std::{fs, io, thread};
 
 /// JSON Lines Viewer – Terminal-UI to view JSON line files (e.g. application logs) or Zip files containing such files
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 #[command(version, about, long_about, after_help=format!("\
 {style}Program Navigation:{style:#}
-  * Use cursor keys and page keys to scroll on a screen
-  * `Enter` opens a detail screen for the selected line; `Esc` goes back to the parent screen (also exits program on main screen)
-  * Use `Ctrl-f` to open a Find dialog; `Esc` leaves the Find dialog; `down/up` jumps to the next/previous finding; a match/miss is indicated by green/red brackets
-  * Use `Ctrl-s` to save current settings. Actual settings are always coming from commandline options and the config file if it exists
+  * Use cursor keys and page keys to scroll on a screen; vim-style keys `j`/`k`/`h`/`l`, `g`/`G` and `Ctrl-d`/`Ctrl-u` (half page) work too
+  * `Ctrl-PageDown`/`Ctrl-PageUp` jumps to the first line of the next/previous source (input file); with `--tabs` it switches to the next/previous tab (`Alt-t` switches to the next one too)
+  * `Enter` opens a detail screen for the selected line (or nested object/array – an array lists its items by index, e.g. `items[1]`); `Esc` goes back to the parent screen (also exits program on main screen)
+  * Click on a line to select it, click again to open it; the mouse wheel scrolls
+  * Use `Ctrl-f` to open a Find dialog; `Esc` leaves the Find dialog; `down/up` jumps to the next/previous finding – or, with an empty search string, `up/down` recalls the earlier searches (kept in the `.state.toml` file); a match/miss is indicated by green/red brackets; `Ctrl-i` toggles case-insensitive matching, `Ctrl-r` toggles regex matching, `Ctrl-z` toggles fuzzy matching (typing jumps to the best matching line), `Ctrl-v` toggles matching the lines on the main screen as shown (instead of their raw JSON), `Ctrl-t` toggles highlighting the matches only (instead of jumping to the first one while typing); several terms separated by spaces must all match and a term like `payment|refund` matches either of its alternatives; on the main screen a `key:text` term finds the text only in the field `key`
+  * Use `Ctrl-o` on the main screen to cycle the sort order through the ordered fields (or those of the selected line)
+  * Use `Alt-p` on the main screen to switch to the next field order preset of the config file (`[presets.<name>]` with `fields_order` and `fields_suppressed`); after the last one the field order without preset is used again
+  * Use `Ctrl-l` on the main screen to filter lines by a field (`key=value`, `key~substring` or a numeric comparison with `<`, `<=`, `>`, `>=`, e.g. `duration_ms>500`); `Enter` keeps the filter, `Esc` removes it
+  * Use `Ctrl-e` on the main screen to export the visible (filtered) lines to a file (as JSON lines, a JSON array or CSV – see `--export-format`); `Enter` writes the file, `Esc` cancels
+  * Use `Ctrl-n` on the main screen to open another file instead of the loaded ones – or `Alt-n` to append its lines; `Enter` loads the file, `Esc` cancels
+  * Use `F5` on the main screen to reload the input files (and the files opened since) – e.g. after they were rewritten; the selected line is kept if it still exists
+  * Use `Ctrl-p` on the main screen to list the value of a field (given as JSON Pointer, e.g. `/http/status`) for all visible lines; `Enter` or `Esc` goes back to the main screen at the selected line
+  * Use `Ctrl-g` on the main screen to count the lines per value of a field (histogram); `Enter` on a value filters the lines by it
+  * On the main and the detail screen `e` toggles hiding fields with a null or empty value
+  * On the main screen `t` toggles a table view with the ordered fields (`--field-order`) as aligned columns
+  * On the main screen `o` toggles between the selected fields (`--only`) and all fields
+  * On the main screen `v` starts (or ends) a range of lines at the selected line; move to extend it, `y` copies its lines to the clipboard, `Esc` cancels it
+  * On the main screen `m` sets (or removes) a bookmark on the selected line, marked with `●`; `n`/`N` jumps to the next/previous bookmark
+  * On the main screen `w` toggles cutting long lines at the screen edge (marked with `…`); `p` toggles pretty-printing each line across multiple rows
+  * On the value detail screen `p` toggles between pretty-printed and compact JSON; `w` toggles line wrapping (scroll horizontally when off); `d` decodes a string containing a JSON document (embedded JSON); `-`/`+` collapses/expands the nested objects and arrays of pretty-printed JSON level by level
+  * On the detail screen `r` toggles showing the whole line verbatim – as it is in the input
+  * On the detail screen `+`/`-` moves the selected field up/down in the field order (an unordered field joins it at the end); `x` suppresses the selected field on the main screen (or shows it again); `Ctrl-s` saves these settings
+  * Use `Ctrl-y` to copy the selected value to the clipboard
+  * Use `Ctrl-s` to save current settings (including the recent sessions). Actual settings are always coming from commandline options and the config file if it exists; quitting with changed settings, which are not saved, asks for confirmation
+  * Viewing a single file again resumes at the line selected last time – with its bookmarks (kept in a `.state.toml` file next to the config file)
+  * `F1` or `?` shows the key bindings; keys can be remapped in the `[keys]` section of the config file, e.g. `exit = [\"Esc\", \"Ctrl-q\"]`
 ", style=anstyle::Style::new().bold().underline()))]
 struct Args {
-    /// JSON line input files - `.json` or `.zip` files(s) containing `.json` files
+    /// JSON line input files (or JSON files with a top level array) - `.json`/`.jsonl`/`.ndjson`, CSV/TSV files with a header row (`.csv`/`.tsv`), `.gz`/`.bz2`/`.xz`/`.lzma` or `.zip` files(s) containing such files (also gzipped), named pipes (read in the background) or `http(s)://` URLs; reads from stdin if omitted
     files: Vec<PathBuf>,
 
     /// fields displayed in-front; separated by comma
     #[arg(short, long, value_delimiter = ',')]
     field_order: Option<Vec<String>>,
 
+    /// read the field order from a file – separated by newline or comma; `--field-order` takes precedence
+    #[arg(long, value_name = "PATH")]
+    field_order_file: Option<PathBuf>,
+
     /// suppressed fields; separated by comma
     #[arg(short, long)]
     suppressed_fields: Option<Vec<String>>,
+
+    /// start with this field order preset of the config file (`[presets.<name>]`);
+    /// `--field-order` and `--suppressed-fields` take precedence
+    #[arg(long, value_name = "NAME")]
+    preset: Option<String>,
+
+    /// show only these fields on the main screen, in this order (taking precedence over the field order and the suppressed fields); separated by comma
+    #[arg(long, value_name = "FIELDS", value_delimiter = ',')]
+    only: Vec<String>,
+
+    /// hide fields with a null or empty value (`null`, `""`, `[]`, `{}`)
+    #[arg(long)]
+    suppress_empty: bool,
+
+    /// only show lines with a matching field, e.g. `level=ERROR`, `message~timeout` or `duration_ms>500` (also `<`, `<=`, `>=`); may be repeated
+    #[arg(long = "filter", value_name = "FILTER")]
+    filters: Vec<FieldFilter>,
+
+    /// sort lines by the given field – numerically if possible, lexically otherwise
+    #[arg(long, value_name = "FIELD")]
+    sort_by: Option<String>,
+
+    /// interleave the lines of all sources chronologically by the given timestamp field (ISO-8601 string or epoch number)
+    #[arg(long, value_name = "TIMESTAMP_FIELD")]
+    merge_by: Option<String>,
+
+    /// collapse consecutive identical lines into one, annotated with the number of repeats
+    #[arg(long)]
+    dedup: bool,
+
+    /// start with the number of lines per value of the given field (histogram); `Enter` on a value filters the lines by it
+    #[arg(long, value_name = "FIELD")]
+    count_by: Option<String>,
+
+    /// start with the line of this number (in its file) selected – of the first file containing it, if there are several
+    #[arg(long, value_name = "LINE", conflicts_with = "count_by")]
+    goto: Option<usize>,
+
+    /// open this field of the `--goto` line on the detail screens – its value, or its fields if it is an object or array
+    #[arg(long, value_name = "FIELD", requires = "goto")]
+    field: Option<String>,
+
+    /// stop loading after that many lines (in total) – to keep the memory usage bounded for huge files
+    #[arg(long, value_name = "N", conflicts_with = "follow")]
+    max_lines: Option<usize>,
+
+    /// keep only the last N lines of each file (or of stdin) – for huge files, when only the recent lines matter
+    #[arg(long, value_name = "N", conflicts_with = "max_lines")]
+    tail: Option<usize>,
+
+    /// field with the log level to color the lines by – red for errors, yellow for warnings, dim for debug and trace;
+    /// defaults to `level` or `severity`
+    #[arg(long, value_name = "FIELD")]
+    level_field: Option<String>,
+
+    /// take numeric values in CSV/TSV files as JSON numbers (instead of strings)
+    #[arg(long)]
+    csv_numbers: bool,
+
+    /// file format of the lines exported with `Ctrl-e`: `json-lines`, `json-array` or `csv`
+    /// (with the ordered fields as columns – or all fields without a field order)
+    #[arg(long, value_name = "FORMAT", default_value_t = ExportFormat::JsonLines)]
+    export_format: ExportFormat,
+
+    /// flag the lines with duplicate keys in an object (of which only the last value is shown);
+    /// the line details list the values of all occurrences
+    #[arg(long)]
+    warn_duplicate_keys: bool,
+
+    /// field with a timestamp (ISO-8601 string or epoch number) to show as local time
+    #[arg(long, value_name = "FIELD")]
+    time_field: Option<String>,
+
+    /// only show lines with a timestamp in the time field at or after this time
+    /// (ISO-8601 timestamp, date or epoch number)
+    #[arg(long, value_name = "TIME", requires = "time_field", value_parser = parse_time_bound)]
+    from: Option<DateTime<Utc>>,
+
+    /// only show lines with a timestamp in the time field at or before this time
+    /// (ISO-8601 timestamp, date or epoch number)
+    #[arg(long, value_name = "TIME", requires = "time_field", value_parser = parse_time_bound)]
+    to: Option<DateTime<Utc>>,
+
+    /// format of the time field (see https://docs.rs/chrono/latest/chrono/format/strftime)
+    #[arg(long, value_name = "FORMAT", default_value = "%Y-%m-%d %H:%M:%S%.3f", value_parser = parse_time_format)]
+    time_format: String,
+
+    /// show nested fields with dotted keys (e.g. `http.status`, `tags[0]`) on the detail screen instead of drilling down
+    #[arg(long)]
+    flatten: bool,
+
+    /// show the source file name in front of each line – color-coded and cut to the given width
+    #[arg(long, value_name = "WIDTH", num_args = 0..=1, default_missing_value = "12")]
+    show_source: Option<usize>,
+
+    /// show each source (input file) as a tab – one at a time on the main screen
+    #[arg(long)]
+    tabs: bool,
+
+    /// show the line number (in its file) in front of each line; prefixed by the number of the file, if there are several
+    #[arg(long)]
+    line_numbers: bool,
+
+    /// pretty-print each line across multiple rows on the main screen (toggle with `p`)
+    #[arg(long)]
+    pretty: bool,
+
+    /// show a dimmed `│` per indentation level of pretty-printed JSON – on the main screen and the value detail screen
+    #[arg(long)]
+    indent_guides: bool,
+
+    /// pin a header row with the names of the ordered fields (the table columns in table view) above the lines on the main screen
+    #[arg(long)]
+    pin_header: bool,
+
+    /// show a clock and the number of loaded lines (and megabytes) on the status line – handy when following files
+    #[arg(long)]
+    clock: bool,
+
+    /// follow JSON line files for appended lines (like `tail -f`)
+    #[arg(long)]
+    follow: bool,
+
+    /// config file to load settings from and save them to; defaults to `json-lines-viewer.toml` in the user's config dir
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// interval in milliseconds to redraw the screen while following files (or showing the clock); defaults to 250.
+    /// Without anything to redraw, the viewer just waits for input
+    #[arg(long, value_name = "MS")]
+    poll_ms: Option<u64>,
+
+    /// reload the config file when it changes; commandline options still take precedence
+    #[arg(long)]
+    watch_config: bool,
+
+    /// select the input files from the recent sessions; that's the default, if no files are given and nothing is piped to stdin
+    #[arg(long, conflicts_with = "files")]
+    recent: bool,
+
+    /// print the number of lines passing the filters (`--filter`, `--from`/`--to`, `--dedup`) and exit – without the terminal UI
+    #[arg(long, conflicts_with_all = ["follow", "recent"])]
+    count: bool,
 }
 
 fn main() -> anyhow::Result<()> {
-    let args = Args::parse();
-    let props: Props = init_props(&args).context("failed to init props")?;
-
-    let lines = load_files(&args.files).context("failed to load files")?;
+    let mut args = Args::parse();
+    let mut props: Props = init_props(&mut args).context("failed to init props")?;
 
     terminal::install_panic_hook();
+
+    let select_recent_session =
+        args.recent || (!args.count && args.files.is_empty() && io::stdin().is_terminal() && !props.recent_sessions.is_empty());
+    let files = if select_recent_session {
+        if props.recent_sessions.is_empty() {
+            bail!("no recent sessions saved yet");
+        }
+        match recent_sessions::select_recent_session(&props).context("failed to select a recent session")? {
+            Some(files) => files,
+            None => return Ok(()),
+        }
+    } else {
+        args.files.clone()
+    };
+
+    if !files.is_empty() && !args.count {
+        props.add_recent_session(&files);
+    }
+
+    // resume at the last selected line, when a single file is viewed again
+    let last_line = match files.as_slice() {
+        [file] if !args.count && !follow::is_fifo(file) && !is_url(file) => {
+            props.state_file_path().map(|state_file| LastLine::open(state_file, file))
+        }
+        _ => None,
+    };
+
+    let stdin_lines = match files.is_empty() {
+        true => Some(load_lines_from_stdin(args.max_lines, args.tail).context("failed to load lines from stdin")?),
+        false => None,
+    };
+    // named pipes never end – they are read in the background instead of being loaded
+    let (fifos, files): (Vec<PathBuf>, Vec<PathBuf>) = files.into_iter().partition(|e| follow::is_fifo(e));
+    let progress = Arc::new(LoadProgress::new(files.len()));
+    let loader = {
+        let (args, progress) = (args.clone(), progress.clone());
+        thread::spawn(move || load_input(&args, &files, &fifos, stdin_lines, &progress))
+    };
+
+    let config_reloads = args.watch_config.then(|| {
+        let args = args.clone();
+        props.watch(move |props| apply_commandline_overrides(&args, props))
+    });
+
+    let options = ViewOptions {
+        line_filters: args.filters,
+        time_range: (args.from.is_some() || args.to.is_some()).then(|| TimeRange {
+            field: args.time_field.clone().unwrap_or_default(),
+            from: args.from,
+            to: args.to,
+        }),
+        sort: args.sort_by.map(|field| LineSort { field, descending: false }),
+        dedup: args.dedup,
+        flatten: args.flatten,
+        source_badge_width: args.show_source,
+        tabs: args.tabs,
+        line_numbers: args.line_numbers,
+        pretty: args.pretty,
+        indent_guides: args.indent_guides,
+        pin_header: args.pin_header,
+        clock: args.clock,
+        field_selection: args.only,
+        count_by: args.count_by,
+        goto: args.goto.map(|line_nr| GotoTarget {
+            line_nr,
+            field: args.field,
+        }),
+        level_field: args.level_field,
+        warn_duplicate_keys: args.warn_duplicate_keys,
+        csv_numbers: args.csv_numbers,
+        export_format: args.export_format,
+        time_format: args.time_field.map(|field| TimeFormat {
+            field,
+            format: args.time_format,
+        }),
+    };
+
+    if args.count {
+        return print_count(props, options, &progress, loader);
+    }
+
     let terminal = terminal::init_terminal().context("failed to initialize terminal")?;
 
-    if let Err(err) = run_app(terminal, props, lines) {
+    if let Err(err) = run_app(terminal, props, options, progress, loader, config_reloads, last_line) {
         eprintln!("{err:?}");
     }
 
@@ -60,118 +313,268 @@ fn main() -> anyhow::Result<()> {
 fn run_app(
     mut terminal: Terminal<impl Backend>,
     props: Props,
-    lines: RawJsonLines,
+    options: ViewOptions,
+    progress: Arc<LoadProgress>,
+    loader: JoinHandle<anyhow::Result<Input>>,
+    config_reloads: Option<Receiver<anyhow::Result<Props>>>,
+    last_line: Option<LastLine>,
 ) -> Result<(), anyhow::Error> {
     let terminal_size = terminal.size().map_err(|e| anyhow!("{e}")).context("failed to get terminal size")?;
-    let mut model = Model::new(props, terminal_size, &lines);
+    let mut model = Model::new(props, terminal_size, RawJsonLines::default(), options);
+    model.show_loading(progress);
+    let state_file = model.props.state_file_path();
+    if let Some(state_file) = &state_file {
+        model.set_find_history(last_lines::find_history(state_file));
+    }
+    let mut loader = Some(loader);
+    let mut followed_lines = None;
+    let mut reload = None;
 
     while model.active_screen != Screen::Done {
+        if let Some(loader) = loader.take_if(|e| e.is_finished()) {
+            let input = loader.join().map_err(|_| anyhow!("loading thread panicked"))??;
+            model.loaded(input.lines);
+            if let Some(last_line) = &last_line {
+                if let Some(line_idx) = last_line.get() {
+                    model.select_raw_line(line_idx);
+                }
+                model.set_bookmarks(last_line.bookmarks());
+            }
+            model.go_to_start_target();
+            followed_lines = input.followed_lines;
+            reload = input.reload;
+        }
+
         // Render the current view
         terminal
             .draw(|f| terminal::view(&mut model, f))
             .map_err(|e| anyhow!("{e}"))
             .context("failed to draw to terminal")?;
 
-        // Handle events and map to a Message
-        let mut current_msg = event::handle_event(&model).context("failed to handle event")?;
+        // Handle events and map to a Message; without anything to redraw periodically, the next event is awaited
+        // (no CPU usage while idle)
+        let redraw_periodically = loader.is_some() || followed_lines.is_some() || config_reloads.is_some() || model.shows_clock();
+        let poll_interval = redraw_periodically.then(|| model.props.poll_interval());
+        if let Some(msg) = event::handle_event(&model, poll_interval).context("failed to handle event")? {
+            model = model.step(msg);
+        }
+
+        if model.take_reload_request() {
+            let progress = LoadProgress::new(1);
+            let lines = match &reload {
+                Some(reload) => reload(&progress),
+                None => Err(anyhow!("only input files can be reloaded – not stdin or named pipes")),
+            };
+            model.reloaded(lines, &progress);
+        }
+
+        if let Some(receiver) = &followed_lines {
+            for line in receiver.try_iter() {
+                model.append_line(line.source_name, line.line_nr, line.content);
+            }
+        }
 
-        // Process updates as long as they return a non-None message
-        while let Some(msg) = current_msg {
-            let (next_model, next_message) = model.updated(msg);
-            model = next_model;
-            current_msg = next_message;
+        if let Some(receiver) = &config_reloads {
+            for props in receiver.try_iter() {
+                model.reload_props(props);
+            }
         }
     }
 
+    if let Some(last_line) = last_line
+        && let Some(line_idx) = model.selected_raw_line_idx()
+    {
+        let bookmarks = model.bookmarks().iter().copied().collect();
+        last_line
+            .save(line_idx, bookmarks)
+            .context("failed to save the last selected line")?;
+    }
+    if let Some(state_file) = &state_file
+        && !model.find_history().is_empty()
+    {
+        last_lines::save_find_history(state_file, model.find_history()).context("failed to save the find history")?;
+    }
+
     Ok(())
 }
 
-fn init_props(args: &Args) -> anyhow::Result<Props> {
-    let mut props = Props::init().context("failed to load props")?;
+/// prints the number of lines passing the filters – as the main screen would show them
+fn print_count(
+    props: Props,
+    options: ViewOptions,
+    progress: &LoadProgress,
+    loader: JoinHandle<anyhow::Result<Input>>,
+) -> anyhow::Result<()> {
+    let input = loader.join().map_err(|_| anyhow!("loading thread panicked"))??;
+    for note in progress.take_notes() {
+        eprintln!("{note}");
+    }
+    let options = ViewOptions {
+        tabs: false,
+        count_by: None,
+        ..options
+    };
+    let mut model = Model::new(props, Size::default(), RawJsonLines::default(), options);
+    model.loaded(input.lines);
+    println!("{}", model.num_visible_lines());
+    Ok(())
+}
 
-    if let Some(e) = &args.field_order {
-        props.fields_order = e.clone();
+/// the input lines – loaded by a background thread, while the Loading screen shows the progress
+struct Input {
+    lines: RawJsonLines,
+    /// lines appended to the followed files (or written to the named pipes) later on
+    followed_lines: Option<Receiver<FollowedLine>>,
+    /// loads the input files again; None for stdin and named pipes
+    reload: Option<Reload>,
+}
+
+type Reload = Box<dyn Fn(&LoadProgress) -> anyhow::Result<RawJsonLines> + Send>;
+
+/// loads the lines of the input files (or takes the lines read from stdin) and starts following the files
+/// and reading the named pipes
+fn load_input(
+    args: &Args,
+    files: &[PathBuf],
+    fifos: &[PathBuf],
+    stdin_lines: Option<RawJsonLines>,
+    progress: &LoadProgress,
+) -> anyhow::Result<Input> {
+    let reload = (stdin_lines.is_none() && fifos.is_empty()).then(|| {
+        let (args, files) = (args.clone(), files.to_vec());
+        Box::new(move |progress: &LoadProgress| load_lines(&args, &files, progress)) as Reload
+    });
+    let lines = match stdin_lines {
+        Some(mut lines) => {
+            if let Some(field) = &args.merge_by {
+                lines.merge_by_timestamp(field);
+            }
+            lines
+        }
+        None => load_lines(args, files, progress)?,
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    if args.follow {
+        follow::follow_files(files, &lines, &sender);
+    }
+    for fifo in fifos {
+        follow::read_fifo(fifo, &sender);
     }
+    let followed_lines = (args.follow || !fifos.is_empty()).then_some(receiver);
+    Ok(Input {
+        lines,
+        followed_lines,
+        reload,
+    })
+}
 
-    if let Some(e) = &args.suppressed_fields {
-        props.fields_suppressed = e.clone();
+/// loads the lines of the input files – interleaved by `--merge-by`
+fn load_lines(
+    args: &Args,
+    files: &[PathBuf],
+    progress: &LoadProgress,
+) -> anyhow::Result<RawJsonLines> {
+    let mut lines = load_files(files, args.max_lines, args.tail, args.csv_numbers, progress).context("failed to load files")?;
+    if let Some(field) = &args.merge_by {
+        lines.merge_by_timestamp(field);
+    }
+    Ok(lines)
+}
+
+fn init_props(args: &mut Args) -> anyhow::Result<Props> {
+    // taken as `--field-order`, so it applies on config reloads as well
+    if args.field_order.is_none()
+        && let Some(path) = &args.field_order_file
+    {
+        args.field_order = Some(read_field_order_file(path)?);
     }
 
+    let mut props = Props::init(args.config.clone()).context("failed to load props")?;
+    if let Some(preset) = &args.preset
+        && !props.presets.contains_key(preset)
+    {
+        let names: Vec<&str> = props.presets.keys().map(String::as_str).collect();
+        bail!("unknown preset '{preset}' – the config file has: {}", names.join(", "));
+    }
+    apply_commandline_overrides(args, &mut props);
     Ok(props)
 }
 
-fn load_files(files: &[PathBuf]) -> anyhow::Result<RawJsonLines> {
-    let mut raw_lines = RawJsonLines::default();
-
-    for path in files {
-        match path.extension()
-            .and_then(|e| e.to_str())
-            .map(|e| e.to_ascii_lowercase())
-            .as_deref()
-        {
-            Some("json") => load_lines_from_json(&mut raw_lines, path).with_context(|| format!("failed to load lines from {path:?}"))?,
-            Some("zip") => load_lines_from_zip(&mut raw_lines, path).with_context(|| format!("failed to load lines from {path:?}"))?,
-            _ => eprintln!("unknown file extension: '{}'", path.to_string_lossy()),
-        }
+/// commandline options take precedence over the settings of the config file
+fn apply_commandline_overrides(
+    args: &Args,
+    props: &mut Props,
+) {
+    if let Some(e) = &args.preset {
+        props.apply_preset(e);
     }
 
-    Ok(raw_lines)
-}
+    if let Some(e) = &args.field_order {
+        *props.active_fields_order_mut() = e.clone();
+    }
 
-fn load_lines_from_json(
-    raw_lines: &mut RawJsonLines,
-    path: &Path,
-) -> anyhow::Result<()> {
-    let json_file = File::open(path).context("failed to open json")?;
-    let json_file = io::BufReader::new(json_file);
+    if let Some(e) = &args.suppressed_fields {
+        *props.active_fields_suppressed_mut() = e.clone();
+    }
 
-    for (line_nr, line) in json_file.lines().enumerate() {
-        let line = line.context("failed to read json line")?;
-        let file_name = path
-            .file_name()
-            .context("BUG: json path is missing filename")?
-            .to_string_lossy()
-            .into();
-        let source_name = SourceName::JsonFile(file_name);
+    if args.suppress_empty {
+        props.suppress_empty_fields = true;
+    }
 
-        raw_lines.push(source_name, line_nr + 1, line);
+    if let Some(e) = args.poll_ms {
+        props.poll_ms = Some(e);
     }
+}
 
-    Ok(())
+/// the field names in the file – one per line or separated by comma; blank ones are ignored
+fn read_field_order_file(path: &Path) -> anyhow::Result<Vec<String>> {
+    let content = fs::read_to_string(path).with_context(|| format!("failed to read field order file {path:?}"))?;
+    Ok(content
+        .split(['\n', ','])
+        .map(str::trim)
+        .filter(|e| !e.is_empty())
+        .map(String::from)
+        .collect())
 }
 
-fn load_lines_from_zip(
-    raw_lines: &mut RawJsonLines,
-    path: &Path,
-) -> anyhow::Result<()> {
-    let zip_file = File::open(path).context("failed to open zip")?;
-    let mut archive = zip::ZipArchive::new(zip_file).context("failed to parse zip")?;
+fn parse_time_format(format: &str) -> Result<String, String> {
+    if StrftimeItems::new(format).any(|e| e == Item::Error) {
+        return Err(format!("invalid time format '{format}'"));
+    }
+    Ok(format.to_string())
+}
 
-    for i in 0..archive.len() {
-        let f = archive
-            .by_index(i)
-            .with_context(|| format!("failed to get file with index {i} from zip"))?;
+/// reads JSON lines piped into stdin – must happen before the terminal enters raw mode
+fn load_lines_from_stdin(
+    max_lines: Option<usize>,
+    tail: Option<usize>,
+) -> anyhow::Result<RawJsonLines> {
+    let stdin = io::stdin();
+    if stdin.is_terminal() {
+        bail!("no input files given and nothing piped to stdin (see --help)");
+    }
 
-        if !f.is_file() || !f.name().to_ascii_lowercase().ends_with(".json") {
-            continue;
+    let mut lines = LoadedLines::new(max_lines, tail);
+    let mut num_lossy_lines = 0;
+    for (line_nr, line) in lossy_lines(stdin.lock()).enumerate() {
+        let (line, lossy) = line.context("failed to read line from stdin")?;
+        if !lines.reserve_line() {
+            eprintln!("note: stdin truncated – line limit reached");
+            break;
         }
+        let source_name = SourceName::JsonFile("<stdin>".into());
 
-        let json_file = f.name().to_string();
-        let f = io::BufReader::new(f);
-
-        for (line_nr, line) in f.lines().enumerate() {
-            let line = line.context("failed to read line from file in zip")?;
-            let zip_file = path
-                .file_name()
-                .context("BUG: zip path is missing filename")?
-                .to_string_lossy()
-                .into();
-            let json_file = json_file.clone();
-            let source_name = SourceName::JsonInZip { zip_file, json_file };
-
-            raw_lines.push(source_name, line_nr + 1, line);
-        }
+        num_lossy_lines += usize::from(lossy);
+        lines.push(source_name, line_nr + 1, line);
+    }
+    if num_lossy_lines > 0 {
+        eprintln!("note: stdin has {num_lossy_lines} lines with invalid UTF-8 – decoded lossily");
+    }
+    let (raw_lines, num_dropped_lines) = lines.finish();
+    if num_dropped_lines > 0 {
+        eprintln!("note: {num_dropped_lines} earlier lines of stdin dropped – keeping the last ones only");
     }
 
-    Ok(())
+    Ok(raw_lines)
 }
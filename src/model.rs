@@ -1,41 +1,239 @@
+use crate::clipboard;
+use crate::export::ExportFormat;
+use crate::filter::{FieldFilter, FilterOperator, TimeRange};
+use crate::histogram::Histogram;
+use crate::progress::LoadProgress;
 use crate::props::Props;
-use crate::raw_json_lines::RawJsonLines;
-use ratatui::prelude::{Color, Line, Size, Span, Style, Stylize};
+use crate::raw_json_lines::{ATTRIBUTE_SEPARATOR, Field, RawJsonLine, RawJsonLines, SourceName, is_empty_value, parse_timestamp};
+use crate::sort::LineSort;
+use chrono::Local;
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use ratatui::prelude::{Color, Line, Size, Span, Style, Stylize, Text};
 use ratatui::style::Styled;
 use ratatui::text::ToSpan;
 use ratatui::widgets::{ListItem, ListState};
-use std::cell::Cell;
-use std::cmp;
+use regex::{Regex, RegexBuilder};
+use rustc_hash::FxHashMap;
+use std::borrow::Cow;
+use std::cell::{LazyCell, RefCell};
+use std::collections::BTreeSet;
+use std::fs::File;
 use std::num::NonZero;
-use std::ops::Add;
+use std::ops::{Add, Range, RangeInclusive};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::{cmp, io, mem};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-#[derive(Clone)]
-pub struct Model<'a> {
+pub struct Model {
     pub active_screen: Screen,
-    pub raw_json_lines: &'a RawJsonLines,
+    pub raw_json_lines: RawJsonLines,
+    line_filters: Vec<FieldFilter>,
+    time_range: Option<TimeRange>,
+    /// filter entered in the filter dialog; applies in addition to `line_filters`
+    interactive_filter: Option<FieldFilter>,
+    sort: Option<LineSort>,
+    time_format: Option<TimeFormat>,
+    /// show nested fields with dotted keys on the ObjectDetails screen instead of drilling down
+    flatten: bool,
+    /// the only fields rendered on the Main screen (`--only`) – in this order; empty to render all fields
+    field_selection: Vec<String>,
+    source_badge_width: Option<usize>,
+    /// show the line numbers in front of each line on the main screen
+    line_numbers: bool,
+    /// largest line number of all lines – for the width of the line numbers
+    max_line_nr: usize,
+    /// show a dimmed `│` per indentation level of pretty-printed JSON
+    indent_guides: bool,
+    /// show the field names in a header row above the lines on the Main screen
+    pin_header: bool,
+    /// show a clock and the number of loaded lines (and bytes) on the status line
+    clock: bool,
+    /// total length of all loaded lines (including line breaks)
+    num_loaded_bytes: usize,
+    /// collapse consecutive visible lines with identical content into one
+    dedup: bool,
+    /// indices of the raw lines shown on the main screen – those passing all line filters, in sort order
+    visible_lines: Vec<usize>,
+    /// number of identical lines a visible line stands for (by raw line index); only present for collapsed lines
+    repeats: FxHashMap<usize, usize>,
+    /// bookmarked lines (by raw line index)
+    bookmarks: BTreeSet<usize>,
+    /// show one source at a time on the Main screen – with a tab bar
+    tabs: bool,
+    /// source id of the shown source in tabs mode
+    active_tab: usize,
+    /// the selected raw line of the tabs switched away from (by source id)
+    tab_selections: FxHashMap<usize, usize>,
     pub props: Props,
+    /// settings (`props`) changed at runtime, which are not saved yet
+    settings_changed: bool,
+    /// asking whether to quit without saving the changed settings
+    quit_confirmation: bool,
     pub view_state: ModelViewState,
     pub terminal_size: Size,
-    num_fields_high_water_mark: Cell<usize>,
+    /// widths of the table view columns (one per ordered field) – the widest value rendered so far, bounded
+    table_column_widths: RefCell<Vec<usize>>,
+    /// number of leading fields skipped when rendering the lines on the Main screen (horizontal scrolling)
     line_rendering_field_offset: usize,
     last_action_result: String,
     find_task: Option<FindTask>,
+    filter_task: Option<FilterTask>,
+    export_task: Option<ExportTask>,
+    open_file_task: Option<OpenFileTask>,
+    projection_task: Option<ProjectionTask>,
+    /// JSON Pointer of the field shown on the Projection screen
+    projection: Option<String>,
+    histogram_task: Option<HistogramTask>,
+    histogram: Option<Histogram>,
+    /// field to show the Histogram screen for, once the lines are loaded
+    count_by: Option<String>,
+    /// line (and field) to show, once the lines are loaded
+    goto: Option<GotoTarget>,
+    /// field with the log level to color the lines by on the Main screen; None to try `level` and `severity`
+    level_field: Option<String>,
+    /// flag the lines with duplicate keys on the Main screen and list them on the ObjectDetails screen
+    warn_duplicate_keys: bool,
+    /// take numeric values in CSV/TSV files as JSON numbers when opening a file
+    csv_numbers: bool,
+    /// file format of the exported lines
+    export_format: ExportFormat,
+    /// progress of the input loading shown on the Loading screen
+    load_progress: Option<Arc<LoadProgress>>,
+    /// reloading the input files is requested – done by the caller, see [Model::reloaded]
+    reload_requested: bool,
+    /// the files opened on the Main screen – loaded again on reloads (after the input files, unless they were replaced)
+    opened_files: Vec<PathBuf>,
+    /// an opened file replaced the input files – so reloads leave them out
+    input_files_replaced: bool,
+    /// search strings of the closed find dialogs – recalled by `up/down` in the find dialog; oldest first
+    find_history: Vec<String>,
+}
+
+const VALUE_SCREEN_HORIZONTAL_SCROLL_STEP: u16 = 4;
+const MAX_TABLE_COLUMN_WIDTH: usize = 32;
+const MAX_FIND_HISTORY: usize = 100;
+const LINE_GONE_NOTE: &str = "(line no longer present – `Esc` goes back)";
+const KEY_WITHOUT_FUNCTION: &str = "key not bound here (press ? for help)";
+const TABLE_COLUMN_GAP: &str = "  ";
+const SOURCE_BADGE_COLORS: [Color; 6] = [Color::Cyan, Color::Magenta, Color::Yellow, Color::Green, Color::Blue, Color::Red];
+
+/// view options from the commandline, which are not part of the saved settings
+#[derive(Default)]
+pub struct ViewOptions {
+    pub line_filters: Vec<FieldFilter>,
+    /// only show lines with a timestamp in this range
+    pub time_range: Option<TimeRange>,
+    pub sort: Option<LineSort>,
+    pub dedup: bool,
+    pub flatten: bool,
+    /// width of the source badge in front of each line on the main screen; None to show no badge
+    pub source_badge_width: Option<usize>,
+    /// show the line number in front of each line on the main screen
+    pub line_numbers: bool,
+    /// pretty-print the lines on the main screen across multiple rows
+    pub pretty: bool,
+    /// show indent guides in pretty-printed JSON
+    pub indent_guides: bool,
+    /// pin a header row with the field names above the lines on the main screen
+    pub pin_header: bool,
+    /// show a clock and the number of loaded lines on the status line
+    pub clock: bool,
+    /// the only fields to show on the main screen; empty to show all fields
+    pub field_selection: Vec<String>,
+    pub time_format: Option<TimeFormat>,
+    /// field to show the Histogram screen for, once the lines are loaded
+    pub count_by: Option<String>,
+    /// line (and field) to show, once the lines are loaded
+    pub goto: Option<GotoTarget>,
+    /// field with the log level to color the lines by; None to try `level` and `severity`
+    pub level_field: Option<String>,
+    /// flag the lines with duplicate keys
+    pub warn_duplicate_keys: bool,
+    /// take numeric values in CSV/TSV files as JSON numbers (instead of strings)
+    pub csv_numbers: bool,
+    /// file format of the exported lines
+    pub export_format: ExportFormat,
+    /// show the sources as tabs – one at a time on the Main screen
+    pub tabs: bool,
+}
+
+/// a line by its number (in its file) – and optionally a field of it to open on the detail screens
+pub struct GotoTarget {
+    pub line_nr: usize,
+    pub field: Option<String>,
+}
+
+/// renders the timestamp in `field` as local time in the given (strftime) format
+pub struct TimeFormat {
+    pub field: String,
+    pub format: String,
 }
 
 #[derive(Clone)]
 pub struct ModelViewState {
     pub main_window_list_state: ListState,
     pub object_detail_list_state: ListState,
-    pub selected_object_detail_field_name: Option<String>,
+    /// path of the selected field on the ObjectDetails screen – relative to `object_detail_path`
+    pub selected_object_detail_field: Option<Vec<String>>,
+    /// path of keys (or array indices) to the nested object shown on the ObjectDetails screen
+    pub object_detail_path: Vec<String>,
+    /// list states of the parent objects in `object_detail_path`
+    pub object_detail_parent_list_states: Vec<ListState>,
+    /// ObjectDetails list states of the lines visited before (by raw line index) – restored when entering a line again
+    pub object_detail_list_states_by_line: FxHashMap<usize, ListState>,
     pub value_screen_vertical_scroll_offset: u16,
+    pub value_screen_horizontal_scroll_offset: u16,
+    /// wrap long lines on the ValueDetails screen; otherwise they can be scrolled horizontally
+    pub value_screen_wrap: bool,
+    /// text line of the current find position on the ValueDetails screen
+    /// (the scroll offset can't be used as it is corrected during rendering)
+    pub value_screen_find_line: usize,
+    /// render JSON objects and arrays on the ValueDetails screen pretty-printed instead of compact
+    pub value_screen_pretty_print: bool,
+    /// render a string value containing a JSON document (embedded JSON) on the ValueDetails screen as that document
+    pub value_screen_decode_json: bool,
+    /// nesting level, beyond which the objects and arrays of pretty-printed JSON are collapsed on the ValueDetails screen;
+    /// None to show them all expanded
+    pub value_screen_fold_level: Option<usize>,
+    /// cut lines on the Main screen, which don't fit on the screen, and mark them with a trailing `…`
+    pub main_screen_truncate_lines: bool,
+    /// render the ordered fields on the Main screen as aligned columns
+    pub main_screen_table_view: bool,
+    /// render each line on the Main screen pretty-printed across multiple rows
+    pub main_screen_pretty_print: bool,
+    /// render all fields on the Main screen, despite a field selection (`--only`)
+    pub main_screen_all_fields: bool,
+    /// show the key bindings on top of the current screen
+    pub help_visible: bool,
+    /// position, where a range of lines on the Main screen starts; the range ends at the selected line
+    pub main_screen_range_anchor: Option<usize>,
+    pub histogram_list_state: ListState,
 }
 impl Default for ModelViewState {
     fn default() -> Self {
         ModelViewState {
             main_window_list_state: ListState::default().with_selected(Some(0)),
             object_detail_list_state: ListState::default().with_selected(Some(0)),
-            selected_object_detail_field_name: None,
+            selected_object_detail_field: None,
+            object_detail_path: vec![],
+            object_detail_parent_list_states: vec![],
+            object_detail_list_states_by_line: FxHashMap::default(),
             value_screen_vertical_scroll_offset: 0,
+            value_screen_horizontal_scroll_offset: 0,
+            value_screen_wrap: true,
+            value_screen_find_line: 0,
+            value_screen_pretty_print: true,
+            value_screen_decode_json: false,
+            value_screen_fold_level: None,
+            main_screen_truncate_lines: false,
+            main_screen_table_view: false,
+            main_screen_pretty_print: false,
+            main_screen_all_fields: false,
+            help_visible: false,
+            main_screen_range_anchor: None,
+            histogram_list_state: ListState::default().with_selected(Some(0)),
         }
     }
 }
@@ -44,6 +242,27 @@ impl Default for ModelViewState {
 pub struct FindTask {
     pub search_string: String,
     pub found: Option<bool>,
+    pub case_insensitive: bool,
+    pub regex_mode: bool,
+    /// the search string matches fuzzily – its characters in order, but not necessarily adjacent;
+    /// typing jumps to the best matching line
+    pub fuzzy_mode: bool,
+    /// lines on the Main screen are matched as they are shown (e.g. without suppressed fields) instead of their raw JSON
+    pub rendered_mode: bool,
+    /// typing only highlights the matches, instead of jumping to the first one; `down/up` still jump
+    pub highlight_only: bool,
+    /// compiled `search_string` in regex mode; None if it is not a valid regex
+    regex: Option<Regex>,
+    /// matcher for `search_string` in fuzzy mode
+    fuzzy_matcher: Option<Arc<SkimMatcherV2>>,
+    /// the terms of `search_string` (see [Self::terms]) prepared for matching; computed once per search criteria change
+    needles: Vec<Vec<Needle>>,
+    /// indices of all matching lines on the active screen; computed once per search criteria change
+    match_indices: Option<Vec<usize>>,
+    /// 1-based position of the currently selected line among `match_indices`
+    match_ordinal: Option<usize>,
+    /// position in the find history of the recalled search string; None after typing
+    history_pos: Option<usize>,
 }
 impl FindTask {
     pub fn add_search_char(
@@ -51,21 +270,307 @@ impl FindTask {
         c: char,
     ) {
         self.search_string.push(c);
-        self.found = None;
+        self.history_pos = None;
+        self.search_criteria_changed();
     }
     pub fn remove_search_char(&mut self) {
         self.search_string.pop();
+        self.history_pos = None;
+        self.search_criteria_changed();
+    }
+    pub fn toggle_case_insensitive(&mut self) {
+        self.case_insensitive = !self.case_insensitive;
+        self.search_criteria_changed();
+    }
+    pub fn toggle_regex_mode(&mut self) {
+        self.regex_mode = !self.regex_mode;
+        self.fuzzy_mode = false;
+        self.search_criteria_changed();
+    }
+    pub fn toggle_rendered_mode(&mut self) {
+        self.rendered_mode = !self.rendered_mode;
+        self.search_criteria_changed();
+    }
+    pub fn toggle_fuzzy_mode(&mut self) {
+        self.fuzzy_mode = !self.fuzzy_mode;
+        self.regex_mode = false;
+        self.search_criteria_changed();
+    }
+
+    fn search_criteria_changed(&mut self) {
         self.found = None;
+        self.match_indices = None;
+        self.regex = match self.regex_mode {
+            true => RegexBuilder::new(&self.search_string)
+                .case_insensitive(self.case_insensitive)
+                .build()
+                .ok(),
+            false => None,
+        };
+        self.needles = self
+            .terms()
+            .iter()
+            .map(|alternatives| alternatives.iter().map(|e| Needle::new(e, self.case_insensitive)).collect())
+            .collect();
+        self.fuzzy_matcher = self.fuzzy_mode.then(|| {
+            let matcher = SkimMatcherV2::default();
+            Arc::new(match self.case_insensitive {
+                true => matcher.ignore_case(),
+                false => matcher.respect_case(),
+            })
+        });
+    }
+
+    pub fn matches(
+        &self,
+        text: &str,
+    ) -> bool {
+        if self.regex_mode {
+            self.regex.as_ref().is_some_and(|r| r.is_match(text))
+        } else if self.fuzzy_mode {
+            self.fuzzy_score(text).is_some()
+        } else {
+            let text = self.comparable(text);
+            self.needles
+                .iter()
+                .all(|alternatives| alternatives.iter().any(|e| text.contains(e.term.as_str())))
+        }
+    }
+
+    /// matches a line on the Main screen; a `key:text` term (not in regex or fuzzy mode) only matches lines,
+    /// whose field `key` contains the text
+    pub fn matches_line(
+        &self,
+        line: &RawJsonLine,
+    ) -> bool {
+        if self.regex_mode || self.fuzzy_mode {
+            return self.matches(&line.content);
+        }
+        // the line is lowercased once (in case-insensitive mode) – and only if a term without field needs it
+        let content = LazyCell::new(|| self.comparable(&line.content));
+        self.needles.iter().all(|alternatives| {
+            alternatives.iter().any(|needle| match &needle.scope {
+                Some((key, text)) => match line.json() {
+                    Ok(serde_json::Value::Object(o)) => o.get(key).is_some_and(|v| match v {
+                        serde_json::Value::String(s) => self.comparable(s).contains(text.as_str()),
+                        v => self.comparable(&v.to_string()).contains(text.as_str()),
+                    }),
+                    _ => false,
+                },
+                None => content.contains(needle.term.as_str()),
+            })
+        })
+    }
+
+    /// the search string (not in regex or fuzzy mode) as terms separated by whitespace – which must all match;
+    /// a term like `a|b` matches, if one of its alternatives matches
+    fn terms(&self) -> Vec<Vec<&str>> {
+        self.search_string
+            .split_whitespace()
+            .map(|term| term.split('|').filter(|e| !e.is_empty()).collect::<Vec<_>>())
+            .filter(|alternatives| !alternatives.is_empty())
+            .collect()
+    }
+
+    /// how well the text matches in fuzzy mode – higher is better; None if it doesn't match
+    fn fuzzy_score(
+        &self,
+        text: &str,
+    ) -> Option<i64> {
+        self.fuzzy_matcher.as_ref()?.fuzzy_match(text, &self.search_string)
+    }
+
+    /// the text as it is compared to the needles – lowercased in case-insensitive mode
+    fn comparable<'t>(
+        &self,
+        text: &'t str,
+    ) -> Cow<'t, str> {
+        match self.case_insensitive {
+            true => Cow::Owned(text.to_lowercase()),
+            false => Cow::Borrowed(text),
+        }
+    }
+
+    /// returns the byte range of the first match in `text`; fuzzy matches are not marked
+    pub fn find_in(
+        &self,
+        text: &str,
+    ) -> Option<Range<usize>> {
+        if self.search_string.is_empty() || self.fuzzy_mode {
+            return None;
+        }
+
+        if self.regex_mode {
+            // empty matches are skipped, as there is nothing to mark
+            return self.regex.as_ref()?.find_iter(text).find(|m| !m.is_empty()).map(|m| m.range());
+        }
+
+        // the first hit of any term – the longest one at the same position
+        self.needles
+            .iter()
+            .flatten()
+            .filter_map(|needle| self.find_needle(text, &needle.term))
+            .min_by_key(|hit| (hit.start, cmp::Reverse(hit.end)))
+    }
+
+    /// the search string in the find bar – with several terms, each one is underlined and the `|` between alternatives dimmed
+    fn render_search_string(&self) -> Vec<Span<'_>> {
+        let terms = self.terms();
+        if self.regex_mode || self.fuzzy_mode || (terms.len() <= 1 && terms.iter().all(|e| e.len() <= 1)) {
+            return vec![self.search_string.as_str().bold()];
+        }
+        self.search_string
+            .split_inclusive(|c: char| c.is_whitespace() || c == '|')
+            .flat_map(|part| {
+                let separator = part.chars().last().filter(|&c| c.is_whitespace() || c == '|');
+                let text = &part[..part.len() - separator.map_or(0, char::len_utf8)];
+                let separator = &part[text.len()..];
+                [
+                    text.bold().underlined(),
+                    match separator {
+                        "|" => separator.dim(),
+                        _ => separator.into(),
+                    },
+                ]
+            })
+            .collect()
+    }
+
+    /// byte range of the first occurrence of `needle` (lowercased in case-insensitive mode) in `text`
+    fn find_needle(
+        &self,
+        text: &str,
+        needle: &str,
+    ) -> Option<Range<usize>> {
+        if !self.case_insensitive {
+            return text.find(needle).map(|start| start..start + needle.len());
+        }
+
+        // compare char by char, because lowercasing may change the byte length of the text
+        text.char_indices().find_map(|(start, _)| {
+            let mut rest = needle;
+            for (i, c) in text[start..].char_indices() {
+                for lc in c.to_lowercase() {
+                    rest = rest.strip_prefix(lc)?;
+                }
+                if rest.is_empty() {
+                    return Some(start..start + i + c.len_utf8());
+                }
+            }
+            None
+        })
+    }
+}
+
+/// an alternative of a search term – lowercased in case-insensitive mode, except for the field name of a `key:text` term
+#[derive(Clone)]
+struct Needle {
+    term: String,
+    /// field name and text of a `key:text` term (see [field_scope])
+    scope: Option<(String, String)>,
+}
+
+impl Needle {
+    fn new(
+        term: &str,
+        case_insensitive: bool,
+    ) -> Self {
+        let lowercase = |e: &str| match case_insensitive {
+            true => e.to_lowercase(),
+            false => e.to_string(),
+        };
+        Needle {
+            term: lowercase(term),
+            scope: field_scope(term).map(|(key, text)| (key.to_string(), lowercase(text))),
+        }
+    }
+}
+
+/// the field name and the text of a `key:text` search term; a key starts with a letter (or `_`, `@`, `$`),
+/// so that e.g. searching for a time like `12:30` still works
+fn field_scope(term: &str) -> Option<(&str, &str)> {
+    let (key, text) = term.split_once(':')?;
+    let mut chars = key.chars();
+    let valid_key =
+        chars.next().is_some_and(|c| c.is_alphabetic() || "_@$".contains(c)) && chars.all(|c| c.is_alphanumeric() || "_-.@$".contains(c));
+    valid_key.then_some((key, text))
+}
+
+#[derive(Clone, Copy)]
+enum SearchDirection {
+    Forward,
+    Backward,
+}
+
+impl SearchDirection {
+    /// positions of a list with `num_lines` lines in search order, starting at the selected position.
+    /// Without a selection the whole list is searched – from the top (forward) or from the bottom (backward).
+    fn search_order(
+        self,
+        selected: Option<usize>,
+        num_lines: usize,
+        skip_current_line: bool,
+    ) -> Box<dyn Iterator<Item = usize>> {
+        let skip = usize::from(skip_current_line && selected.is_some());
+        match self {
+            SearchDirection::Forward => {
+                let start = selected.unwrap_or(0) + skip;
+                Box::new(start..num_lines)
+            }
+            SearchDirection::Backward => {
+                let end = selected.map_or(num_lines, |e| cmp::min(e + 1 - skip, num_lines));
+                Box::new((0..end).rev())
+            }
+        }
     }
 }
 
+/// input of the filter dialog – `key=value`, `key~substring` or a numeric comparison like `key>500`
+#[derive(Clone, Default)]
+pub struct FilterTask {
+    pub filter_string: String,
+}
+
+/// input of the open file dialog – the file to load; its lines replace the loaded ones or are appended to them
+#[derive(Clone, Default)]
+pub struct OpenFileTask {
+    pub path: String,
+    pub append: bool,
+}
+
+/// input of the export dialog – the file to write the visible lines to
+#[derive(Clone, Default)]
+pub struct ExportTask {
+    pub file_name: String,
+}
+
+/// input of the projection dialog – a JSON Pointer to the field to list for all lines, e.g. `/http/status`
+#[derive(Clone, Default)]
+pub struct ProjectionTask {
+    pub pointer: String,
+}
+
+/// input of the histogram dialog – the field to count the lines by value for
+#[derive(Clone, Default)]
+pub struct HistogramTask {
+    pub field: String,
+}
+
 #[derive(Clone, Default, Eq, PartialEq)]
 pub enum Screen {
     Done,
+    /// shown while the input is loaded in the background
+    Loading,
     #[default]
     Main,
     ObjectDetails,
     ValueDetails,
+    /// the line selected on the main screen verbatim – as it is in the input
+    RawLine,
+    /// the value of a single field for all visible lines; shares the list state with the Main screen
+    Projection,
+    /// the number of lines per value of a field
+    Histogram,
 }
 
 #[derive(Clone, Copy, Eq, PartialEq)]
@@ -76,6 +581,14 @@ pub enum Message {
     ScrollDown,
     PageUp,
     PageDown,
+    HalfPageUp,
+    HalfPageDown,
+    /// to the first line of the previous source
+    PreviousSource,
+    /// to the first line of the next source
+    NextSource,
+    /// to the next source in tabs mode
+    NextTab,
     ScrollLeft,
     ScrollRight,
     Enter,
@@ -85,33 +598,538 @@ pub enum Message {
     OpenFindTask,
     CharacterInput(char),
     Backspace,
+    ToggleFindCaseInsensitive,
+    ToggleFindRegexMode,
+    ToggleFindFuzzyMode,
+    ToggleFindRenderedMode,
+    ToggleFindHighlightOnly,
+    CopyValue,
+    OpenFilterTask,
+    CycleSort,
+    CyclePreset,
+    Reload,
+    OpenExportTask,
+    /// opens the dialog to load another file – replacing the loaded lines or appended to them
+    OpenFileTask {
+        append: bool,
+    },
+    OpenProjectionTask,
+    OpenHistogramTask,
+    Help,
+    /// left mouse button pressed on the given terminal row
+    MouseClick {
+        row: u16,
+    },
+    /// a key without a binding
+    UnboundKey,
 }
 
-impl<'a> Model<'a> {
+impl Model {
     pub fn new(
         props: Props,
         terminal_size: Size,
-        raw_json_lines: &'a RawJsonLines,
+        raw_json_lines: RawJsonLines,
+        options: ViewOptions,
     ) -> Self {
-        Self {
+        let mut model = Self {
             active_screen: Default::default(),
             raw_json_lines,
+            line_filters: options.line_filters,
+            time_range: options.time_range,
+            interactive_filter: None,
+            sort: options.sort,
+            dedup: options.dedup,
+            time_format: options.time_format,
+            flatten: options.flatten,
+            field_selection: options.field_selection,
+            source_badge_width: options.source_badge_width,
+            line_numbers: options.line_numbers,
+            max_line_nr: 0,
+            indent_guides: options.indent_guides,
+            pin_header: options.pin_header,
+            clock: options.clock,
+            num_loaded_bytes: 0,
+            visible_lines: vec![],
+            repeats: FxHashMap::default(),
+            bookmarks: BTreeSet::new(),
+            tabs: options.tabs,
+            active_tab: 0,
+            tab_selections: FxHashMap::default(),
             props,
-            view_state: Default::default(),
+            settings_changed: false,
+            quit_confirmation: false,
+            view_state: ModelViewState {
+                main_screen_pretty_print: options.pretty,
+                ..Default::default()
+            },
             terminal_size,
-            num_fields_high_water_mark: Cell::new(0), // gets updated before the first usage
+            table_column_widths: RefCell::new(vec![]),
             line_rendering_field_offset: 0,
             last_action_result: String::new(),
             find_task: None,
+            filter_task: None,
+            export_task: None,
+            open_file_task: None,
+            projection_task: None,
+            projection: None,
+            histogram_task: None,
+            histogram: None,
+            count_by: options.count_by,
+            goto: options.goto,
+            level_field: options.level_field,
+            warn_duplicate_keys: options.warn_duplicate_keys,
+            csv_numbers: options.csv_numbers,
+            export_format: options.export_format,
+            load_progress: None,
+            reload_requested: false,
+            opened_files: vec![],
+            input_files_replaced: false,
+            find_history: vec![],
+        };
+        model.apply_line_filters();
+        model
+    }
+
+    /// shows the Loading screen until the lines are `loaded`
+    pub fn show_loading(
+        &mut self,
+        progress: Arc<LoadProgress>,
+    ) {
+        self.load_progress = Some(progress);
+        self.switch_screen(Screen::Loading);
+    }
+
+    /// takes over the loaded lines and switches from the Loading screen to the Main screen
+    pub fn loaded(
+        &mut self,
+        raw_json_lines: RawJsonLines,
+    ) {
+        self.raw_json_lines = raw_json_lines;
+        self.count_loaded_lines();
+        self.apply_line_filters();
+        if let Some(progress) = self.load_progress.take() {
+            let notes = progress.take_notes();
+            self.last_action_result = match notes.as_slice() {
+                [] => String::new(),
+                [note] => note.clone(),
+                [note, more @ ..] => format!("{note} (+{} more notes)", more.len()),
+            };
+        }
+        if self.active_screen == Screen::Loading {
+            self.switch_screen(Screen::Main);
+            if let Some(field) = self.count_by.take() {
+                self.open_histogram(&field);
+            }
         }
     }
 
+    pub fn render_load_progress(&self) -> String { self.load_progress.as_ref().map(|e| e.render()).unwrap_or_default() }
+
+    pub fn shows_clock(&self) -> bool { self.clock }
+
     pub fn has_find_task(&self) -> bool { self.find_task.is_some() }
 
+    pub fn has_filter_task(&self) -> bool { self.filter_task.is_some() }
+
+    pub fn has_export_task(&self) -> bool { self.export_task.is_some() }
+
+    pub fn has_open_file_task(&self) -> bool { self.open_file_task.is_some() }
+
+    pub fn has_projection_task(&self) -> bool { self.projection_task.is_some() }
+
+    pub fn has_histogram_task(&self) -> bool { self.histogram_task.is_some() }
+
+    pub fn has_quit_confirmation(&self) -> bool { self.quit_confirmation }
+
+    fn passes_line_filters(
+        &self,
+        line: &RawJsonLine,
+    ) -> bool {
+        self.line_filters.iter().chain(&self.interactive_filter).all(|f| f.matches(line))
+            && self.time_range.as_ref().is_none_or(|e| e.matches(line))
+            && (!self.tabs || line.source_id == self.active_tab)
+    }
+
+    /// shows the next (or previous) source in tabs mode – at the line selected there before
+    fn switch_tab(
+        &mut self,
+        forward: bool,
+    ) {
+        let num_tabs = self.raw_json_lines.num_sources();
+        if num_tabs < 2 {
+            self.last_action_result = "single source only".to_string();
+            return;
+        }
+        if let Some(line_idx) = self.selected_raw_line_idx() {
+            self.tab_selections.insert(self.active_tab, line_idx);
+        }
+        self.active_tab = match forward {
+            true => (self.active_tab + 1) % num_tabs,
+            false => (self.active_tab + num_tabs - 1) % num_tabs,
+        };
+        self.apply_line_filters();
+        match self.tab_selections.get(&self.active_tab) {
+            Some(&line_idx) => self.select_raw_line(line_idx),
+            None => self.view_state.main_window_list_state.select(Some(0)),
+        }
+    }
+
+    /// the sources as tabs, the shown one highlighted – in tabs mode only
+    pub fn render_tab_bar(&self) -> Option<Line<'_>> {
+        if !self.tabs {
+            return None;
+        }
+        let tabs = (0..self.raw_json_lines.num_sources()).filter_map(|id| {
+            let name = self.raw_json_lines.source_name(id)?.short_name();
+            Some(match id == self.active_tab {
+                true => Span::raw(format!(" {name} ")).reversed(),
+                false => Span::raw(format!(" {name} ")),
+            })
+        });
+        Some(Line::from_iter(tabs))
+    }
+
+    /// recomputes the visible lines after a filter or sort change; the selection stays at (or behind) the selected line
+    fn apply_line_filters(&mut self) {
+        let selected_line_idx = self
+            .view_state
+            .main_window_list_state
+            .selected()
+            .and_then(|pos| self.visible_lines.get(pos).copied())
+            .unwrap_or(0);
+
+        // the positions change – a selected range is given up
+        self.view_state.main_screen_range_anchor = None;
+
+        self.visible_lines = (0..self.raw_json_lines.lines.len())
+            .filter(|&i| self.passes_line_filters(&self.raw_json_lines.lines[i]))
+            .collect();
+
+        if let Some(sort) = &self.sort {
+            let lines = &self.raw_json_lines.lines;
+            self.visible_lines.sort_by(|&a, &b| sort.compare(&lines[a], &lines[b]));
+        }
+        if self.dedup {
+            self.collapse_repeated_lines();
+        }
+
+        let pos = match self.sort {
+            Some(_) => self.visible_lines.iter().position(|&i| i == selected_line_idx).unwrap_or(0),
+            None => self.visible_lines.partition_point(|&i| i < selected_line_idx),
+        };
+        self.view_state
+            .main_window_list_state
+            .select(Some(cmp::min(pos, self.num_visible_lines().saturating_sub(1))));
+    }
+
+    fn toggle_suppress_empty_fields(&mut self) {
+        self.props.suppress_empty_fields = !self.props.suppress_empty_fields;
+        self.settings_changed = true;
+        self.last_action_result = match self.props.suppress_empty_fields {
+            true => "empty fields hidden".to_string(),
+            false => "empty fields shown".to_string(),
+        };
+    }
+
+    /// moves the top level field selected on the ObjectDetails screen up (to the front) or down in the field order;
+    /// an unordered field moving up is appended to the order, the last ordered field moving down is removed from it
+    fn move_selected_field_in_order(
+        &mut self,
+        up: bool,
+    ) {
+        let Some(field) = self.selected_top_level_field() else {
+            self.last_action_result = "only top level fields can be ordered".to_string();
+            return;
+        };
+
+        let order = self.props.active_fields_order_mut();
+        match (order.iter().position(|e| e == &field), up) {
+            (None, true) => order.push(field.clone()),
+            (Some(i), true) if i > 0 => order.swap(i - 1, i),
+            (Some(i), false) if i + 1 < order.len() => order.swap(i, i + 1),
+            (Some(i), false) => {
+                order.remove(i);
+            }
+            _ => return,
+        }
+        self.settings_changed = true;
+        self.table_column_widths.borrow_mut().clear();
+        if self.props.active_fields_order().is_empty() {
+            self.view_state.main_screen_table_view = false;
+        }
+        self.last_action_result = format!("field order: {}", self.props.active_fields_order().join(", "));
+
+        // the selection moves along with the field
+        let (_, field_paths) = self.produce_line_details_screen_content();
+        if let Some(pos) = field_paths.iter().position(|e| e.as_slice() == [field.as_str()]) {
+            self.view_state.object_detail_list_state.select(Some(pos));
+        }
+    }
+
+    /// adds the top level field selected on the ObjectDetails screen to the suppressed fields – or removes it from them;
+    /// a suppressed field leaves the field order
+    fn toggle_selected_field_suppressed(&mut self) {
+        let Some(field) = self.selected_top_level_field() else {
+            self.last_action_result = "only top level fields can be suppressed".to_string();
+            return;
+        };
+
+        match self.props.active_fields_suppressed().iter().position(|e| e == &field) {
+            Some(i) => {
+                self.props.active_fields_suppressed_mut().remove(i);
+                self.last_action_result = format!("field {field} shown");
+            }
+            None => {
+                self.props.active_fields_order_mut().retain(|e| e != &field);
+                if self.props.active_fields_order().is_empty() {
+                    self.view_state.main_screen_table_view = false;
+                }
+                self.table_column_widths.borrow_mut().clear();
+                self.last_action_result = format!("field {field} suppressed");
+                self.props.active_fields_suppressed_mut().push(field);
+            }
+        }
+        self.settings_changed = true;
+    }
+
+    /// name of the field selected on the ObjectDetails screen – if it is a top level field
+    fn selected_top_level_field(&self) -> Option<String> {
+        match (
+            self.view_state.object_detail_path.is_empty(),
+            self.view_state.selected_object_detail_field.as_deref(),
+        ) {
+            (true, Some([field])) => Some(field.clone()),
+            _ => None,
+        }
+    }
+
+    /// takes over the props reloaded from the changed config file; the recent sessions and the preset switched to in this run are kept
+    pub fn reload_props(
+        &mut self,
+        props: anyhow::Result<Props>,
+    ) {
+        match props {
+            Ok(mut props) => {
+                props.recent_sessions = mem::take(&mut self.props.recent_sessions);
+                props.preset = self.props.preset.take().filter(|e| props.presets.contains_key(e));
+                self.props = props;
+                self.settings_changed = false;
+                if self.props.active_fields_order().is_empty() {
+                    self.view_state.main_screen_table_view = false;
+                }
+                self.table_column_widths.borrow_mut().clear();
+                self.last_action_result = "config reloaded".to_string();
+            }
+            Err(e) => self.last_action_result = format!("Error: failed to reload config – {e}"),
+        }
+    }
+
+    /// collapses consecutive visible lines with identical content into the first one and counts the repeats
+    fn collapse_repeated_lines(&mut self) {
+        let lines = &self.raw_json_lines.lines;
+        self.repeats.clear();
+        let mut collapsed: Vec<usize> = Vec::with_capacity(self.visible_lines.len());
+        for &idx in &self.visible_lines {
+            match collapsed.last() {
+                Some(&prev) if lines[prev].content == lines[idx].content => *self.repeats.entry(prev).or_insert(1) += 1,
+                _ => collapsed.push(idx),
+            }
+        }
+        self.visible_lines = collapsed;
+    }
+
+    /// positions of the visible lines in the selected range (between the range anchor and the selected line)
+    pub fn main_screen_range(&self) -> Option<RangeInclusive<usize>> {
+        let anchor = self.view_state.main_screen_range_anchor?;
+        let selected = self.view_state.main_window_list_state.selected()?;
+        let last = self.num_visible_lines().checked_sub(1)?;
+        Some(cmp::min(anchor, selected)..=cmp::min(cmp::max(anchor, selected), last))
+    }
+
+    /// copies the lines of the selected range to the clipboard (as they are in the input) and ends the range on success
+    fn copy_range(&mut self) {
+        let Some(range) = self.main_screen_range() else {
+            return;
+        };
+        let text = range
+            .clone()
+            .filter_map(|pos| self.visible_line(pos))
+            .map(|e| e.content.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.last_action_result = match clipboard::copy(&text) {
+            Ok(_) => {
+                self.view_state.main_screen_range_anchor = None;
+                format!("Ok: copied {} lines", range.count())
+            }
+            Err(e) => format!("Error: {e}"),
+        };
+    }
+
+    /// number of identical lines the visible line at `pos` stands for (1 if it's not a collapsed line)
+    pub fn visible_line_repeats(
+        &self,
+        pos: usize,
+    ) -> usize {
+        self.visible_lines.get(pos).and_then(|i| self.repeats.get(i)).copied().unwrap_or(1)
+    }
+
+    /// switches to the next sort order: unsorted → field ascending → field descending → next field … → unsorted.
+    /// Sort fields are the ordered fields, otherwise the fields of the selected line.
+    fn cycle_sort(&mut self) {
+        let mut fields: Vec<String> = self.sort.iter().map(|e| e.field.clone()).collect();
+        let candidates = match self.props.active_fields_order().is_empty() {
+            false => self.props.active_fields_order().to_vec(),
+            true => self
+                .view_state
+                .main_window_list_state
+                .selected()
+                .and_then(|pos| self.visible_line(pos))
+                .and_then(|line| line.json().ok()?.as_object().map(|o| o.keys().cloned().collect()))
+                .unwrap_or_default(),
+        };
+        fields.extend(candidates.into_iter().filter(|e| self.sort.as_ref().is_none_or(|s| &s.field != e)));
+
+        self.sort = match self.sort.take() {
+            None => fields.first().map(|field| LineSort {
+                field: field.clone(),
+                descending: false,
+            }),
+            Some(LineSort { field, descending: false }) => Some(LineSort { field, descending: true }),
+            Some(LineSort { descending: true, .. }) => fields.get(1).map(|field| LineSort {
+                field: field.clone(),
+                descending: false,
+            }),
+        };
+        self.apply_line_filters();
+
+        self.last_action_result = match &self.sort {
+            Some(sort) => format!("sorted by {sort}"),
+            None => "unsorted".to_string(),
+        };
+    }
+
+    /// switches to the next field order preset of the config file – after the last one back to the field order without preset
+    fn cycle_preset(&mut self) {
+        let names: Vec<String> = self.props.presets.keys().cloned().collect();
+        if names.is_empty() {
+            self.last_action_result = "no presets configured".to_string();
+            return;
+        }
+        let next = match &self.props.preset {
+            Some(preset) => names.iter().position(|e| e == preset).and_then(|i| names.get(i + 1)),
+            None => names.first(),
+        };
+        match next {
+            Some(name) => {
+                self.props.apply_preset(name);
+                self.last_action_result = format!("preset {name}");
+            }
+            None => {
+                self.props.preset = None;
+                self.last_action_result = "no preset".to_string();
+            }
+        }
+        self.table_column_widths.borrow_mut().clear();
+        if self.props.active_fields_order().is_empty() {
+            self.view_state.main_screen_table_view = false;
+        }
+    }
+
+    fn update_interactive_filter(&mut self) {
+        let filter_string = &self.filter_task.as_ref().expect("filter task should be set").filter_string;
+        self.interactive_filter = filter_string.parse().ok();
+        self.apply_line_filters();
+    }
+
+    /// number of lines shown on the main screen
+    pub fn num_visible_lines(&self) -> usize { self.visible_lines.len() }
+
+    /// returns the raw line shown at the given position on the main screen
+    fn visible_line(
+        &self,
+        pos: usize,
+    ) -> Option<&RawJsonLine> {
+        self.visible_lines.get(pos).map(|&i| &self.raw_json_lines.lines[i])
+    }
+
+    /// updates the line number width and the loaded bytes after (re)loading the lines
+    fn count_loaded_lines(&mut self) {
+        let lines = &self.raw_json_lines.lines;
+        self.max_line_nr = lines.iter().map(|e| e.line_nr).max().unwrap_or(0);
+        self.num_loaded_bytes = lines.iter().map(|e| e.content.len() + 1).sum();
+    }
+
+    /// appends a line (e.g. from a followed file); a selected last line on the main screen moves on to the new one
+    pub fn append_line(
+        &mut self,
+        source_name: SourceName,
+        line_nr: usize,
+        content: String,
+    ) {
+        let selected_pos = self.view_state.main_window_list_state.selected();
+        let range_anchor = self.view_state.main_screen_range_anchor;
+        let selected_last_line = self.active_screen == Screen::Main && selected_pos == Some(self.num_visible_lines().saturating_sub(1));
+
+        self.max_line_nr = cmp::max(self.max_line_nr, line_nr);
+        self.num_loaded_bytes += content.len() + 1;
+        self.raw_json_lines.push(source_name, line_nr, content);
+
+        let idx = self.raw_json_lines.lines.len() - 1;
+        let lines = &self.raw_json_lines.lines;
+        if !self.passes_line_filters(&lines[idx]) {
+            return;
+        }
+        let pos = match &self.sort {
+            Some(sort) => self
+                .visible_lines
+                .partition_point(|&i| sort.compare(&lines[i], &lines[idx]).is_le()),
+            None => self.visible_lines.len(),
+        };
+        if self.dedup
+            && let Some(&prev) = pos.checked_sub(1).and_then(|e| self.visible_lines.get(e))
+            && lines[prev].content == lines[idx].content
+        {
+            *self.repeats.entry(prev).or_insert(1) += 1;
+            return;
+        }
+        self.visible_lines.insert(pos, idx);
+
+        if selected_last_line && pos == self.num_visible_lines() - 1 {
+            self.view_state.main_window_list_state.select(Some(pos));
+        } else if let Some(selected_pos) = selected_pos
+            && selected_pos >= pos
+            && self.num_visible_lines() > 1
+        {
+            // keep the selected line
+            self.view_state.main_window_list_state.select(Some(selected_pos + 1));
+        }
+        if let Some(anchor) = range_anchor
+            && anchor >= pos
+        {
+            self.view_state.main_screen_range_anchor = Some(anchor + 1);
+        }
+        if let Some(task) = self.find_task.as_mut() {
+            task.match_indices = None;
+        }
+    }
+
+    /// processes the message and the messages following from it – like the event loop does, but without a terminal
+    pub fn step(
+        self,
+        msg: Message,
+    ) -> Model {
+        let (mut model, mut next_msg) = self.updated(msg);
+        while let Some(msg) = next_msg {
+            (model, next_msg) = model.updated(msg);
+        }
+        model
+    }
+
     pub fn updated(
         mut self,
         msg: Message,
-    ) -> (Model<'a>, Option<Message>) {
+    ) -> (Model, Option<Message>) {
         self.last_action_result.clear();
 
         match msg {
@@ -119,90 +1137,268 @@ impl<'a> Model<'a> {
                 self.terminal_size = size;
                 (self, None)
             }
+            // any key closes the help
+            _ if self.view_state.help_visible => {
+                self.view_state.help_visible = false;
+                (self, None)
+            }
+            Message::Help => {
+                self.view_state.help_visible = true;
+                (self, None)
+            }
             Message::SaveSettings => {
                 self.save_settings();
                 (self, None)
             }
+            Message::UnboundKey => {
+                self.last_action_result = KEY_WITHOUT_FUNCTION.to_string();
+                (self, None)
+            }
             _ => {
-                if self.has_find_task() {
+                if self.has_quit_confirmation() {
                     match msg {
-                        Message::OpenFindTask => {
-                            // workaround to enable searching for slashes too
-                            (self, Some(Message::CharacterInput('/')))
+                        Message::CharacterInput('y') => {
+                            self.switch_screen(Screen::Done);
+                            (self, None)
+                        }
+                        Message::CharacterInput('s') => {
+                            self.quit_confirmation = false;
+                            self.save_settings();
+                            if !self.settings_changed {
+                                self.switch_screen(Screen::Done);
+                            }
+                            (self, None)
+                        }
+                        Message::CharacterInput('n') | Message::Exit => {
+                            self.quit_confirmation = false;
+                            (self, None)
                         }
+                        _ => self.not_handled(msg),
+                    }
+                } else if self.has_export_task() {
+                    match msg {
                         Message::CharacterInput(c) => {
-                            self.find_task.as_mut().unwrap().add_search_char(c);
-                            self.find_next(false);
+                            self.export_task.as_mut().unwrap().file_name.push(c);
                             (self, None)
                         }
                         Message::Backspace => {
-                            self.find_task.as_mut().unwrap().remove_search_char();
-                            self.find_next(false);
+                            self.export_task.as_mut().unwrap().file_name.pop();
                             (self, None)
                         }
-                        Message::ScrollUp => {
-                            self.find_previous();
+                        Message::Enter => {
+                            let task = self.export_task.take().unwrap();
+                            if !task.file_name.is_empty() {
+                                self.export_visible_lines(Path::new(&task.file_name));
+                            }
                             (self, None)
                         }
-                        Message::ScrollDown => {
-                            self.find_next(true);
+                        Message::Exit => {
+                            self.export_task = None;
+                            (self, None)
+                        }
+                        _ => self.not_handled(msg),
+                    }
+                } else if self.has_open_file_task() {
+                    match msg {
+                        Message::CharacterInput(c) => {
+                            self.open_file_task.as_mut().unwrap().path.push(c);
+                            (self, None)
+                        }
+                        Message::Backspace => {
+                            self.open_file_task.as_mut().unwrap().path.pop();
+                            (self, None)
+                        }
+                        Message::Enter => {
+                            let task = self.open_file_task.take().unwrap();
+                            if !task.path.is_empty() {
+                                self.open_file(Path::new(&task.path), task.append);
+                            }
                             (self, None)
                         }
-                        Message::Enter => (self, Some(Message::ScrollDown)),
                         Message::Exit => {
-                            self.find_task = None;
+                            self.open_file_task = None;
                             (self, None)
                         }
-                        _ => (self, None),
+                        _ => self.not_handled(msg),
+                    }
+                } else if self.has_projection_task() {
+                    match msg {
+                        Message::CharacterInput(c) => {
+                            self.projection_task.as_mut().unwrap().pointer.push(c);
+                            (self, None)
+                        }
+                        Message::Backspace => {
+                            self.projection_task.as_mut().unwrap().pointer.pop();
+                            (self, None)
+                        }
+                        Message::Enter => {
+                            let task = self.projection_task.take().unwrap();
+                            if !task.pointer.is_empty() {
+                                self.open_projection(task.pointer);
+                            }
+                            (self, None)
+                        }
+                        Message::Exit => {
+                            self.projection_task = None;
+                            (self, None)
+                        }
+                        _ => self.not_handled(msg),
+                    }
+                } else if self.has_histogram_task() {
+                    match msg {
+                        Message::CharacterInput(c) => {
+                            self.histogram_task.as_mut().unwrap().field.push(c);
+                            (self, None)
+                        }
+                        Message::Backspace => {
+                            self.histogram_task.as_mut().unwrap().field.pop();
+                            (self, None)
+                        }
+                        Message::Enter => {
+                            let task = self.histogram_task.take().unwrap();
+                            if !task.field.is_empty() {
+                                self.open_histogram(&task.field);
+                            }
+                            (self, None)
+                        }
+                        Message::Exit => {
+                            self.histogram_task = None;
+                            (self, None)
+                        }
+                        _ => self.not_handled(msg),
+                    }
+                } else if self.has_filter_task() {
+                    match msg {
+                        Message::CharacterInput(c) => {
+                            self.filter_task.as_mut().unwrap().filter_string.push(c);
+                            self.update_interactive_filter();
+                            (self, None)
+                        }
+                        Message::Backspace => {
+                            self.filter_task.as_mut().unwrap().filter_string.pop();
+                            self.update_interactive_filter();
+                            (self, None)
+                        }
+                        Message::Enter => {
+                            // close the dialog, keep the filter
+                            self.filter_task = None;
+                            (self, None)
+                        }
+                        Message::Exit => {
+                            self.filter_task = None;
+                            self.interactive_filter = None;
+                            self.apply_line_filters();
+                            (self, None)
+                        }
+                        _ => self.not_handled(msg),
+                    }
+                } else if self.has_find_task() {
+                    match msg {
+                        Message::OpenFindTask => {
+                            // workaround to enable searching for slashes too
+                            (self, Some(Message::CharacterInput('/')))
+                        }
+                        Message::CharacterInput(c) => {
+                            self.find_task.as_mut().unwrap().add_search_char(c);
+                            self.find_as_you_type();
+                            (self, None)
+                        }
+                        Message::Backspace => {
+                            self.find_task.as_mut().unwrap().remove_search_char();
+                            self.find_as_you_type();
+                            (self, None)
+                        }
+                        Message::ScrollUp if self.browses_find_history(true) => {
+                            self.recall_search(true);
+                            (self, None)
+                        }
+                        Message::ScrollDown if self.browses_find_history(false) => {
+                            self.recall_search(false);
+                            (self, None)
+                        }
+                        Message::ScrollUp => {
+                            self.find_previous(true);
+                            (self, None)
+                        }
+                        Message::ScrollDown => {
+                            self.find_next(true);
+                            (self, None)
+                        }
+                        Message::ToggleFindCaseInsensitive => {
+                            self.find_task.as_mut().unwrap().toggle_case_insensitive();
+                            self.find_as_you_type();
+                            (self, None)
+                        }
+                        Message::ToggleFindRegexMode => {
+                            self.find_task.as_mut().unwrap().toggle_regex_mode();
+                            self.find_as_you_type();
+                            (self, None)
+                        }
+                        Message::ToggleFindFuzzyMode => {
+                            self.find_task.as_mut().unwrap().toggle_fuzzy_mode();
+                            self.find_as_you_type();
+                            (self, None)
+                        }
+                        Message::ToggleFindRenderedMode => {
+                            self.find_task.as_mut().unwrap().toggle_rendered_mode();
+                            self.find_as_you_type();
+                            (self, None)
+                        }
+                        Message::ToggleFindHighlightOnly => {
+                            let task = self.find_task.as_mut().unwrap();
+                            task.highlight_only = !task.highlight_only;
+                            (self, None)
+                        }
+                        Message::Enter => (self, Some(Message::ScrollDown)),
+                        Message::Exit => {
+                            self.close_find_task();
+                            (self, None)
+                        }
+                        _ => self.not_handled(msg),
                     }
                 } else {
                     match self.active_screen {
                         Screen::Done => (self, None),
-                        Screen::Main => match msg {
-                            // because the pos is used in other render methods
-                            Message::First => {
-                                self.view_state.main_window_list_state.select_first();
-                                (self, None)
-                            }
-                            Message::Last => {
-                                self.view_state
-                                    .main_window_list_state
-                                    .select(Some(cmp::min(self.raw_json_lines.lines.len() as isize - 1, 0) as usize));
+                        Screen::Loading => match msg {
+                            Message::Exit => {
+                                self.switch_screen(Screen::Done);
                                 (self, None)
                             }
-                            Message::ScrollUp => {
-                                if let Some(pos) = self.view_state.main_window_list_state.selected() {
-                                    self.view_state
-                                        .main_window_list_state
-                                        .select(Some(cmp::max(pos as isize - 1, 0) as usize));
-                                }
+                            _ => self.not_handled(msg),
+                        },
+                        Screen::Main => match msg {
+                            Message::NextSource | Message::NextTab if self.tabs => {
+                                self.switch_tab(true);
                                 (self, None)
                             }
-                            Message::ScrollDown => {
-                                if let Some(pos) = self.view_state.main_window_list_state.selected() {
-                                    self.view_state
-                                        .main_window_list_state
-                                        .select(Some(
-                                            cmp::min(pos as isize + 1, self.raw_json_lines.lines.len() as isize - 1) as usize
-                                        ));
-                                }
+                            Message::PreviousSource if self.tabs => {
+                                self.switch_tab(false);
                                 (self, None)
                             }
-                            Message::PageUp => {
-                                if let Some(pos) = self.view_state.main_window_list_state.selected() {
-                                    self.view_state
-                                        .main_window_list_state
-                                        .select(Some(pos.saturating_sub(self.page_len() as usize)))
+                            Message::MouseClick { row } => {
+                                let list_state = &self.view_state.main_window_list_state;
+                                match self.clicked_list_pos(list_state, row, self.num_visible_lines()) {
+                                    // a click on the selected line opens it
+                                    Some(pos) if list_state.selected() == Some(pos) => (self, Some(Message::Enter)),
+                                    Some(pos) => {
+                                        self.view_state.main_window_list_state.select(Some(pos));
+                                        (self, None)
+                                    }
+                                    None => (self, None),
                                 }
-                                (self, None)
                             }
-                            Message::PageDown => {
-                                if let Some(pos) = self.view_state.main_window_list_state.selected() {
-                                    self.view_state.main_window_list_state.select(Some(cmp::min(
-                                        pos + self.page_len() as usize,
-                                        self.raw_json_lines.lines.len().saturating_sub(1),
-                                    )))
-                                }
+                            // because the pos is used in other render methods
+                            Message::First
+                            | Message::Last
+                            | Message::ScrollUp
+                            | Message::ScrollDown
+                            | Message::PageUp
+                            | Message::HalfPageUp
+                            | Message::PageDown
+                            | Message::HalfPageDown
+                            | Message::PreviousSource
+                            | Message::NextSource => {
+                                self.move_main_window_selection(msg);
                                 (self, None)
                             }
                             Message::ScrollLeft => {
@@ -212,7 +1408,7 @@ impl<'a> Model<'a> {
                                 (self, None)
                             }
                             Message::ScrollRight => {
-                                if self.line_rendering_field_offset + 1 < self.num_fields_high_water_mark.get() {
+                                if self.line_rendering_field_offset + 1 < self.selected_line_num_fields().unwrap_or(0) {
                                     self.line_rendering_field_offset += 1;
                                 }
                                 (self, None)
@@ -221,20 +1417,258 @@ impl<'a> Model<'a> {
                                 self.find_task = Some(FindTask::default());
                                 (self, None)
                             }
+                            Message::CharacterInput('w') => {
+                                self.view_state.main_screen_truncate_lines = !self.view_state.main_screen_truncate_lines;
+                                (self, None)
+                            }
+                            Message::CharacterInput('e') => {
+                                self.toggle_suppress_empty_fields();
+                                (self, None)
+                            }
+                            Message::CharacterInput('p') => {
+                                self.view_state.main_screen_pretty_print = !self.view_state.main_screen_pretty_print;
+                                (self, None)
+                            }
+                            Message::CharacterInput('o') => {
+                                self.toggle_field_selection();
+                                (self, None)
+                            }
+                            Message::CharacterInput('t') => {
+                                if self.main_screen_fields_order().is_empty() {
+                                    self.last_action_result = "table view needs ordered fields (--field-order)".to_string();
+                                } else {
+                                    self.view_state.main_screen_table_view = !self.view_state.main_screen_table_view;
+                                }
+                                (self, None)
+                            }
+                            Message::CycleSort => {
+                                self.cycle_sort();
+                                (self, None)
+                            }
+                            Message::CyclePreset => {
+                                self.cycle_preset();
+                                (self, None)
+                            }
+                            Message::Reload => {
+                                match self.input_files_replaced {
+                                    true => self.reloaded(Ok(RawJsonLines::default()), &LoadProgress::new(self.opened_files.len())),
+                                    false => self.reload_requested = true,
+                                }
+                                (self, None)
+                            }
+                            Message::OpenExportTask => {
+                                self.export_task = Some(ExportTask::default());
+                                (self, None)
+                            }
+                            Message::OpenFileTask { append } => {
+                                self.open_file_task = Some(OpenFileTask {
+                                    path: String::new(),
+                                    append,
+                                });
+                                (self, None)
+                            }
+                            Message::OpenProjectionTask => {
+                                let pointer = self.projection.clone().unwrap_or_default();
+                                self.projection_task = Some(ProjectionTask { pointer });
+                                (self, None)
+                            }
+                            Message::OpenHistogramTask => {
+                                let field = self.histogram.as_ref().map(|e| e.field.clone()).unwrap_or_default();
+                                self.histogram_task = Some(HistogramTask { field });
+                                (self, None)
+                            }
+                            Message::OpenFilterTask => {
+                                let filter_string = self.interactive_filter.as_ref().map(|e| e.to_string()).unwrap_or_default();
+                                self.filter_task = Some(FilterTask { filter_string });
+                                (self, None)
+                            }
                             Message::Enter => {
-                                if self.view_state.main_window_list_state.selected().is_some() {
+                                if let Some(line_idx) = self.selected_raw_line_idx() {
                                     self.switch_screen(Screen::ObjectDetails);
-                                    self.view_state.object_detail_list_state.select(Some(0));
+                                    self.view_state.object_detail_list_state = self
+                                        .view_state
+                                        .object_detail_list_states_by_line
+                                        .get(&line_idx)
+                                        .copied()
+                                        .unwrap_or(ListState::default().with_selected(Some(0)));
+                                    self.view_state.object_detail_path.clear();
+                                    self.view_state.object_detail_parent_list_states.clear();
+                                }
+                                (self, None)
+                            }
+                            Message::CharacterInput('v') => {
+                                self.view_state.main_screen_range_anchor = match self.view_state.main_screen_range_anchor {
+                                    Some(_) => None,
+                                    None => self.view_state.main_window_list_state.selected(),
+                                };
+                                if self.view_state.main_screen_range_anchor.is_some() {
+                                    self.last_action_result = "range: move to extend, `y` copies, `Esc` cancels".to_string();
                                 }
                                 (self, None)
                             }
+                            Message::CharacterInput('y') => {
+                                self.copy_range();
+                                (self, None)
+                            }
+                            Message::CharacterInput('m') => {
+                                self.toggle_bookmark();
+                                (self, None)
+                            }
+                            Message::CharacterInput('n') => {
+                                self.jump_to_bookmark(true);
+                                (self, None)
+                            }
+                            Message::CharacterInput('N') => {
+                                self.jump_to_bookmark(false);
+                                (self, None)
+                            }
+                            Message::Exit if self.view_state.main_screen_range_anchor.is_some() => {
+                                self.view_state.main_screen_range_anchor = None;
+                                (self, None)
+                            }
+                            Message::Exit if self.settings_changed => {
+                                self.quit_confirmation = true;
+                                (self, None)
+                            }
                             Message::Exit => {
                                 self.switch_screen(Screen::Done);
                                 (self, None)
                             }
-                            _ => (self, None),
+                            _ => self.not_handled(msg),
+                        },
+                        Screen::Histogram => match msg {
+                            Message::MouseClick { row } => {
+                                let list_state = &self.view_state.histogram_list_state;
+                                let num_buckets = self.histogram.as_ref().map_or(0, |e| e.buckets.len());
+                                match self.clicked_list_pos(list_state, row, num_buckets) {
+                                    // a click on the selected value filters by it
+                                    Some(pos) if list_state.selected() == Some(pos) => (self, Some(Message::Enter)),
+                                    Some(pos) => {
+                                        self.view_state.histogram_list_state.select(Some(pos));
+                                        (self, None)
+                                    }
+                                    None => (self, None),
+                                }
+                            }
+                            Message::First => {
+                                self.view_state.histogram_list_state.select_first();
+                                (self, None)
+                            }
+                            Message::Last => {
+                                self.view_state.histogram_list_state.select_last();
+                                (self, None)
+                            }
+                            Message::ScrollUp => {
+                                self.view_state.histogram_list_state.scroll_up_by(1);
+                                (self, None)
+                            }
+                            Message::ScrollDown => {
+                                self.view_state.histogram_list_state.scroll_down_by(1);
+                                (self, None)
+                            }
+                            Message::PageUp | Message::HalfPageUp => {
+                                self.view_state.histogram_list_state.scroll_up_by(self.page_scroll_len(msg));
+                                (self, None)
+                            }
+                            Message::PageDown | Message::HalfPageDown => {
+                                self.view_state.histogram_list_state.scroll_down_by(self.page_scroll_len(msg));
+                                (self, None)
+                            }
+                            Message::OpenFindTask => {
+                                self.find_task = Some(FindTask::default());
+                                (self, None)
+                            }
+                            Message::OpenHistogramTask => {
+                                let field = self.histogram.as_ref().map(|e| e.field.clone()).unwrap_or_default();
+                                self.histogram_task = Some(HistogramTask { field });
+                                (self, None)
+                            }
+                            Message::Enter => {
+                                self.filter_by_selected_histogram_value();
+                                (self, None)
+                            }
+                            Message::Exit => {
+                                self.switch_screen(Screen::Main);
+                                (self, None)
+                            }
+                            _ => self.not_handled(msg),
+                        },
+                        Screen::Projection => match msg {
+                            Message::MouseClick { row } => {
+                                let list_state = &self.view_state.main_window_list_state;
+                                match self.clicked_list_pos(list_state, row, self.num_visible_lines()) {
+                                    // a click on the selected line opens it
+                                    Some(pos) if list_state.selected() == Some(pos) => (self, Some(Message::Enter)),
+                                    Some(pos) => {
+                                        self.view_state.main_window_list_state.select(Some(pos));
+                                        (self, None)
+                                    }
+                                    None => (self, None),
+                                }
+                            }
+                            Message::First
+                            | Message::Last
+                            | Message::ScrollUp
+                            | Message::ScrollDown
+                            | Message::PageUp
+                            | Message::HalfPageUp
+                            | Message::PageDown
+                            | Message::HalfPageDown
+                            | Message::PreviousSource
+                            | Message::NextSource => {
+                                self.move_main_window_selection(msg);
+                                (self, None)
+                            }
+                            Message::OpenFindTask => {
+                                self.find_task = Some(FindTask::default());
+                                (self, None)
+                            }
+                            Message::OpenProjectionTask => {
+                                let pointer = self.projection.clone().unwrap_or_default();
+                                self.projection_task = Some(ProjectionTask { pointer });
+                                (self, None)
+                            }
+                            // back to the selected line on the Main screen
+                            Message::Enter | Message::Exit => {
+                                self.switch_screen(Screen::Main);
+                                (self, None)
+                            }
+                            _ => self.not_handled(msg),
                         },
                         Screen::ObjectDetails => match msg {
+                            Message::MouseClick { row } => {
+                                let list_state = &self.view_state.object_detail_list_state;
+                                let num_lines = self.produce_line_details_screen_content().0.len();
+                                match self.clicked_list_pos(list_state, row, num_lines) {
+                                    // a click on the selected field opens it
+                                    Some(pos) if list_state.selected() == Some(pos) => (self, Some(Message::Enter)),
+                                    Some(pos) => {
+                                        self.view_state.object_detail_list_state.select(Some(pos));
+                                        (self, None)
+                                    }
+                                    None => (self, None),
+                                }
+                            }
+                            Message::CharacterInput('e') => {
+                                self.toggle_suppress_empty_fields();
+                                (self, None)
+                            }
+                            Message::CharacterInput('r') => {
+                                self.switch_screen(Screen::RawLine);
+                                (self, None)
+                            }
+                            Message::CharacterInput('+') => {
+                                self.move_selected_field_in_order(true);
+                                (self, None)
+                            }
+                            Message::CharacterInput('-') => {
+                                self.move_selected_field_in_order(false);
+                                (self, None)
+                            }
+                            Message::CharacterInput('x') => {
+                                self.toggle_selected_field_suppressed();
+                                (self, None)
+                            }
                             Message::First => {
                                 self.view_state.object_detail_list_state.select_first();
                                 (self, None)
@@ -251,12 +1685,12 @@ impl<'a> Model<'a> {
                                 self.view_state.object_detail_list_state.scroll_down_by(1);
                                 (self, None)
                             }
-                            Message::PageUp => {
-                                self.view_state.object_detail_list_state.scroll_up_by(self.page_len());
+                            Message::PageUp | Message::HalfPageUp => {
+                                self.view_state.object_detail_list_state.scroll_up_by(self.page_scroll_len(msg));
                                 (self, None)
                             }
-                            Message::PageDown => {
-                                self.view_state.object_detail_list_state.scroll_down_by(self.page_len());
+                            Message::PageDown | Message::HalfPageDown => {
+                                self.view_state.object_detail_list_state.scroll_down_by(self.page_scroll_len(msg));
                                 (self, None)
                             }
                             Message::ScrollLeft | Message::ScrollRight => (self, None),
@@ -268,17 +1702,41 @@ impl<'a> Model<'a> {
                                 self.find_task = Some(FindTask::default());
                                 (self, None)
                             }
+                            Message::CopyValue => {
+                                if self.selected_field_path().is_some() {
+                                    self.copy_value();
+                                }
+                                (self, None)
+                            }
                             Message::Enter => {
-                                self.switch_screen(Screen::ValueDetails);
+                                // lines without a JSON object have no fields to show
+                                if let Some(field_path) = self.selected_field_path()
+                                    && let Some(raw_line) = self.selected_raw_line()
+                                {
+                                    match raw_line.value_at(&field_path) {
+                                        Some(serde_json::Value::Object(o)) if !o.is_empty() => self.enter_nested_object(field_path),
+                                        Some(serde_json::Value::Array(a)) if !a.is_empty() => self.enter_nested_object(field_path),
+                                        _ => self.switch_screen(Screen::ValueDetails),
+                                    }
+                                }
                                 (self, None)
                             }
                             Message::Exit => {
-                                self.switch_screen(Screen::Main);
+                                if self.view_state.object_detail_path.is_empty() {
+                                    if let Some(line_idx) = self.selected_raw_line_idx() {
+                                        self.view_state
+                                            .object_detail_list_states_by_line
+                                            .insert(line_idx, self.view_state.object_detail_list_state);
+                                    }
+                                    self.switch_screen(Screen::Main);
+                                } else {
+                                    self.leave_nested_object();
+                                }
                                 (self, None)
                             }
-                            _ => (self, None),
+                            _ => self.not_handled(msg),
                         },
-                        Screen::ValueDetails => match msg {
+                        Screen::ValueDetails | Screen::RawLine => match msg {
                             Message::ScrollUp => {
                                 self.view_state.value_screen_vertical_scroll_offset =
                                     self.view_state.value_screen_vertical_scroll_offset.saturating_sub(1);
@@ -288,24 +1746,78 @@ impl<'a> Model<'a> {
                                 self.view_state.value_screen_vertical_scroll_offset += 1; // value is corrected during rendering
                                 (self, None)
                             }
-                            Message::PageUp => {
-                                self.view_state.value_screen_vertical_scroll_offset =
-                                    self.view_state.value_screen_vertical_scroll_offset.saturating_sub(self.page_len());
+                            Message::PageUp | Message::HalfPageUp => {
+                                self.view_state.value_screen_vertical_scroll_offset = self
+                                    .view_state
+                                    .value_screen_vertical_scroll_offset
+                                    .saturating_sub(self.page_scroll_len(msg));
+                                (self, None)
+                            }
+                            Message::PageDown | Message::HalfPageDown => {
+                                self.view_state.value_screen_vertical_scroll_offset += self.page_scroll_len(msg); // value is corrected during rendering
+                                (self, None)
+                            }
+                            Message::First => {
+                                self.view_state.value_screen_vertical_scroll_offset = 0;
+                                (self, None)
+                            }
+                            Message::Last => {
+                                self.view_state.value_screen_vertical_scroll_offset = u16::MAX; // value is corrected during rendering
+                                (self, None)
+                            }
+                            Message::ScrollLeft => {
+                                self.view_state.value_screen_horizontal_scroll_offset = self
+                                    .view_state
+                                    .value_screen_horizontal_scroll_offset
+                                    .saturating_sub(VALUE_SCREEN_HORIZONTAL_SCROLL_STEP);
+                                (self, None)
+                            }
+                            Message::ScrollRight => {
+                                // value is corrected during rendering
+                                self.view_state.value_screen_horizontal_scroll_offset += VALUE_SCREEN_HORIZONTAL_SCROLL_STEP;
+                                (self, None)
+                            }
+                            Message::CharacterInput('w') => {
+                                self.view_state.value_screen_wrap = !self.view_state.value_screen_wrap;
+                                (self, None)
+                            }
+                            Message::CopyValue => {
+                                self.copy_value();
+                                (self, None)
+                            }
+                            Message::CharacterInput('p') if self.active_screen == Screen::ValueDetails => {
+                                self.view_state.value_screen_pretty_print = !self.view_state.value_screen_pretty_print;
+                                (self, None)
+                            }
+                            Message::CharacterInput('d') if self.active_screen == Screen::ValueDetails => {
+                                self.view_state.value_screen_decode_json = !self.view_state.value_screen_decode_json;
+                                self.view_state.value_screen_vertical_scroll_offset = 0;
+                                self.view_state.value_screen_fold_level = None;
+                                (self, None)
+                            }
+                            Message::CharacterInput('-') if self.active_screen == Screen::ValueDetails => {
+                                self.fold_value_details(true);
+                                (self, None)
+                            }
+                            Message::CharacterInput('+') if self.active_screen == Screen::ValueDetails => {
+                                self.fold_value_details(false);
                                 (self, None)
                             }
-                            Message::PageDown => {
-                                self.view_state.value_screen_vertical_scroll_offset += self.page_len(); // value is corrected during rendering
+                            Message::OpenFindTask => {
+                                self.find_task = Some(FindTask::default());
+                                self.view_state.value_screen_find_line =
+                                    self.value_screen_line_of_row(self.view_state.value_screen_vertical_scroll_offset);
                                 (self, None)
                             }
-                            // Message::OpenFindTask => {
-                            //     self.find_task = Some(FindTask::default());
-                            //     (self, None)
-                            // }
                             Message::Exit => {
                                 self.switch_screen(Screen::ObjectDetails);
                                 (self, None)
                             }
-                            _ => (self, None),
+                            Message::CharacterInput('r') if self.active_screen == Screen::RawLine => {
+                                self.switch_screen(Screen::ObjectDetails);
+                                (self, None)
+                            }
+                            _ => self.not_handled(msg),
                         },
                     }
                 }
@@ -317,254 +1829,1842 @@ impl<'a> Model<'a> {
         &mut self,
         new_screen: Screen,
     ) {
+        if matches!(new_screen, Screen::ValueDetails | Screen::RawLine) {
+            self.view_state.value_screen_vertical_scroll_offset = 0;
+            self.view_state.value_screen_horizontal_scroll_offset = 0;
+            self.view_state.value_screen_find_line = 0;
+            self.view_state.value_screen_fold_level = None;
+        }
         self.active_screen = new_screen;
-        self.find_task = None;
+        self.close_find_task();
+    }
+
+    /// moves the selected line of the Main screen (and the Projection screen) according to the navigation message
+    fn move_main_window_selection(
+        &mut self,
+        msg: Message,
+    ) {
+        let Some(pos) = self.view_state.main_window_list_state.selected() else {
+            return;
+        };
+        let last_pos = self.num_visible_lines().saturating_sub(1);
+        let pos = match msg {
+            Message::First => 0,
+            Message::Last => last_pos,
+            Message::ScrollUp => pos.saturating_sub(1),
+            Message::ScrollDown => cmp::min(pos + 1, last_pos),
+            Message::PageUp | Message::HalfPageUp => pos.saturating_sub(self.page_scroll_items(pos, msg)),
+            Message::PageDown | Message::HalfPageDown => cmp::min(pos + self.page_scroll_items(pos, msg), last_pos),
+            Message::PreviousSource => self.previous_source_pos(pos).unwrap_or(pos),
+            Message::NextSource => self.next_source_pos(pos).unwrap_or(pos),
+            _ => pos,
+        };
+        self.view_state.main_window_list_state.select(Some(pos));
+    }
+
+    /// position of the next visible line from another source than the one at `pos`
+    fn next_source_pos(
+        &self,
+        pos: usize,
+    ) -> Option<usize> {
+        let source_id = self.visible_line(pos)?.source_id;
+        (pos + 1..self.num_visible_lines()).find(|&e| self.visible_line(e).is_some_and(|e| e.source_id != source_id))
+    }
+
+    /// position of the first line of the previous source – the start of the run of lines from another source before `pos`
+    fn previous_source_pos(
+        &self,
+        pos: usize,
+    ) -> Option<usize> {
+        let source_id_at = |pos: usize| self.visible_line(pos).map(|e| e.source_id);
+        let source_id = source_id_at(pos)?;
+        let last_pos = (0..pos).rev().find(|&e| source_id_at(e) != Some(source_id))?;
+        let previous_source_id = source_id_at(last_pos);
+        let first_pos = (0..last_pos)
+            .rev()
+            .take_while(|&e| source_id_at(e) == previous_source_id)
+            .last()
+            .unwrap_or(last_pos);
+        Some(first_pos)
+    }
+
+    /// shows the Projection screen for the field at the given JSON Pointer (a leading `/` may be omitted)
+    fn open_projection(
+        &mut self,
+        pointer: String,
+    ) {
+        let pointer = match pointer.starts_with('/') {
+            true => pointer,
+            false => format!("/{pointer}"),
+        };
+        self.projection = Some(pointer);
+        self.switch_screen(Screen::Projection);
+    }
+
+    /// counts the lines per value of the given field and shows them on the Histogram screen
+    fn open_histogram(
+        &mut self,
+        field: &str,
+    ) {
+        self.histogram = Some(Histogram::tally(field, &self.raw_json_lines.lines));
+        self.view_state.histogram_list_state.select(Some(0));
+        self.switch_screen(Screen::Histogram);
+    }
+
+    /// filters the lines on the Main screen by the value selected on the Histogram screen (replacing the interactive filter)
+    fn filter_by_selected_histogram_value(&mut self) {
+        let Some(histogram) = &self.histogram else {
+            return;
+        };
+        let Some((value, _)) = self
+            .view_state
+            .histogram_list_state
+            .selected()
+            .and_then(|i| histogram.buckets.get(i))
+        else {
+            return;
+        };
+        let filter = FieldFilter {
+            key: histogram.field.clone(),
+            operator: FilterOperator::Equals,
+            value: value.clone(),
+        };
+        self.last_action_result = format!("filter: {filter}");
+        self.interactive_filter = Some(filter);
+        self.apply_line_filters();
+        self.switch_screen(Screen::Main);
+    }
+
+    /// e.g. `level – 1234 of 2000 lines`
+    pub fn render_histogram_title(&self) -> String {
+        match &self.histogram {
+            Some(e) => format!("{} – {} of {} lines", e.field, e.num_counted_lines(), e.num_lines),
+            None => String::new(),
+        }
+    }
+
+    /// returns the Histogram screen lines as text: each value with its number of lines (without the bar)
+    pub fn produce_histogram_screen_content(&self) -> Vec<String> {
+        let Some(histogram) = &self.histogram else {
+            return vec![];
+        };
+        let value_width = histogram.buckets.iter().map(|(value, _)| value.width()).max().unwrap_or(0);
+        let value_width = cmp::min(value_width, MAX_TABLE_COLUMN_WIDTH);
+        let count_width = histogram.max_count().to_string().len();
+
+        histogram
+            .buckets
+            .iter()
+            .map(|(value, count)| {
+                let value = truncated(Line::from(value.as_str()), value_width).to_string();
+                format!(
+                    "{value}{}  {count:>count_width$}",
+                    " ".repeat(value_width.saturating_sub(value.width()))
+                )
+            })
+            .collect()
+    }
+
+    /// returns the Histogram screen lines with search hits marked, followed by a bar proportional to the number of lines
+    pub fn produce_histogram_screen_lines(&self) -> Vec<Line<'_>> {
+        let Some(histogram) = &self.histogram else {
+            return vec![];
+        };
+        let content = self.produce_histogram_screen_content();
+        let text_width = content.first().map_or(0, |e| e.width());
+        let bar_width = self.main_screen_line_width().saturating_sub(text_width + 2);
+        let max_count = cmp::max(histogram.max_count(), 1);
+
+        content
+            .into_iter()
+            .zip(&histogram.buckets)
+            .map(|(text, (_, count))| {
+                let mut line = Line::from(self.with_search_hits_marked(text));
+                let bar_len = cmp::max(count * bar_width / max_count, 1);
+                line.push_span(Span::raw("  "));
+                line.push_span(Span::styled(
+                    "█".repeat(bar_len),
+                    Style::new().fg(self.props.theme.number_value_color),
+                ));
+                line
+            })
+            .collect()
+    }
+
+    pub fn render_quit_confirmation_line(&self) -> Line<'_> {
+        " [".to_span()
+            .add("Quit without saving the settings?".bold())
+            .add("  y: quit | n: stay | s: save and quit ] ".to_span())
+            .to_owned()
+    }
+
+    pub fn render_histogram_task_line_left(&self) -> Line<'_> {
+        let Some(task) = &self.histogram_task else {
+            return "".into();
+        };
+
+        " [".to_span()
+            .add("Count lines by field".to_span())
+            .add(": ".bold())
+            .add(task.field.to_span().bold())
+            .add("  ] ".to_span())
+            .to_owned()
+    }
+
+    /// the JSON Pointer of the Projection screen
+    pub fn projection(&self) -> Option<&str> { self.projection.as_deref() }
+
+    /// returns the Projection screen lines as text: the origin of each visible line and the value of the projected field –
+    /// blank, if the line doesn't have it
+    pub fn produce_projection_screen_content(&self) -> Vec<String> {
+        let Some(pointer) = &self.projection else {
+            return vec![];
+        };
+        let origin = |raw_line: &RawJsonLine| {
+            let source_name = self
+                .raw_json_lines
+                .source_name(raw_line.source_id)
+                .map(|e| e.to_string())
+                .unwrap_or_default();
+            format!("{source_name}:{}", raw_line.line_nr)
+        };
+        let origin_width = self
+            .visible_lines
+            .iter()
+            .map(|&i| origin(&self.raw_json_lines.lines[i]).width())
+            .max()
+            .unwrap_or(0);
+
+        self.visible_lines
+            .iter()
+            .map(|&i| {
+                let raw_line = &self.raw_json_lines.lines[i];
+                let value = match raw_line.json().ok().and_then(|e| e.pointer(pointer)) {
+                    Some(serde_json::Value::String(s)) => s.clone(),
+                    Some(e) => format!("{e}"),
+                    None => String::new(),
+                };
+                format!("{:origin_width$}  {value}", origin(raw_line))
+            })
+            .collect()
+    }
+
+    /// returns the Projection screen lines with search hits marked
+    pub fn produce_projection_screen_lines(&self) -> Vec<Line<'_>> {
+        self.produce_projection_screen_content()
+            .into_iter()
+            .map(|e| Line::from(self.with_search_hits_marked(e)))
+            .collect()
+    }
+
+    /// list position of the item shown at the given terminal row; None outside the list items
+    fn clicked_list_pos(
+        &self,
+        list_state: &ListState,
+        row: u16,
+        num_items: usize,
+    ) -> Option<usize> {
+        // row 0 is the top border – followed by the pinned header
+        let mut item_row = row.checked_sub(1 + self.pinned_header_height()).filter(|&e| e < self.page_len())? as usize;
+        let mut pos = list_state.offset();
+        if self.active_screen == Screen::Main && self.view_state.main_screen_pretty_print {
+            while pos < num_items && item_row >= self.main_screen_item_height(pos) {
+                item_row -= self.main_screen_item_height(pos);
+                pos += 1;
+            }
+        } else {
+            pos += item_row;
+        }
+        (pos < num_items).then_some(pos)
+    }
+
+    /// true once after reloading the input files was requested
+    pub fn take_reload_request(&mut self) -> bool { std::mem::take(&mut self.reload_requested) }
+
+    /// replaces the lines with the reloaded input files and the files opened since – keeping the selected line
+    /// (and the bookmarks) where still valid
+    pub fn reloaded(
+        &mut self,
+        lines: anyhow::Result<RawJsonLines>,
+        progress: &LoadProgress,
+    ) {
+        let lines = lines.and_then(|mut lines| {
+            lines.append(self.load_opened_files(progress)?);
+            Ok(lines)
+        });
+        let lines = match lines {
+            Ok(e) => e,
+            Err(e) => {
+                self.last_action_result = format!("Error: {e:#}");
+                return;
+            }
+        };
+        let num_lines = lines.lines.len();
+        let selected_line_idx = self.selected_raw_line_idx();
+        let bookmarks = std::mem::take(&mut self.bookmarks);
+
+        self.replace_lines(lines, false);
+        self.set_bookmarks(bookmarks);
+        if let Some(line_idx) = selected_line_idx.filter(|&e| e < num_lines) {
+            self.select_raw_line(line_idx);
+        }
+
+        self.last_action_result = match progress.take_notes().into_iter().next() {
+            Some(note) => note,
+            None => format!("Ok: {num_lines} lines reloaded"),
+        };
+    }
+
+    /// index of the raw line selected on the main screen
+    pub fn selected_raw_line_idx(&self) -> Option<usize> {
+        let pos = self.view_state.main_window_list_state.selected()?;
+        self.visible_lines.get(pos).copied()
+    }
+
+    pub fn bookmarks(&self) -> &BTreeSet<usize> { &self.bookmarks }
+
+    /// sets the bookmarks (by raw line index) – e.g. those of the last session; indices beyond the loaded lines are ignored
+    pub fn set_bookmarks(
+        &mut self,
+        bookmarks: impl IntoIterator<Item = usize>,
+    ) {
+        let num_lines = self.raw_json_lines.lines.len();
+        self.bookmarks = bookmarks.into_iter().filter(|&e| e < num_lines).collect();
+    }
+
+    fn toggle_bookmark(&mut self) {
+        let Some(line_idx) = self.selected_raw_line_idx() else {
+            return;
+        };
+        let action = match self.bookmarks.remove(&line_idx) {
+            true => "bookmark removed",
+            false => {
+                self.bookmarks.insert(line_idx);
+                "bookmark set"
+            }
+        };
+        self.last_action_result = format!("{action} – {} bookmarks", self.bookmarks.len());
+    }
+
+    /// selects the next (or previous) visible bookmarked line on the main screen – wrapping around at the end
+    fn jump_to_bookmark(
+        &mut self,
+        forward: bool,
+    ) {
+        let num_lines = self.num_visible_lines();
+        let selected = self.view_state.main_window_list_state.selected().unwrap_or(0);
+        let bookmarked = |pos: &usize| self.visible_lines.get(*pos).is_some_and(|idx| self.bookmarks.contains(idx));
+        let pos = match forward {
+            true => (selected + 1..num_lines).chain(0..=selected).find(bookmarked),
+            false => (0..selected).rev().chain((selected..num_lines).rev()).find(bookmarked),
+        };
+        match pos {
+            Some(pos) => self.view_state.main_window_list_state.select(Some(pos)),
+            None if self.bookmarks.is_empty() => self.last_action_result = "no bookmarks – `m` sets one".to_string(),
+            None => self.last_action_result = "no visible bookmarks".to_string(),
+        }
+    }
+
+    /// shows the line given by `--goto` – and opens its field given by `--field` like `Enter` does on the detail screens
+    pub fn go_to_start_target(&mut self) {
+        let Some(target) = self.goto.take() else {
+            return;
+        };
+        let Some(line_idx) = self.raw_json_lines.lines.iter().position(|e| e.line_nr == target.line_nr) else {
+            self.last_action_result = format!("line {} not found", target.line_nr);
+            return;
+        };
+        if !self.visible_lines.contains(&line_idx) {
+            self.last_action_result = format!("line {} is filtered out", target.line_nr);
+            return;
+        }
+        self.select_raw_line(line_idx);
+        let Some(field) = target.field else {
+            return;
+        };
+
+        self.switch_screen(Screen::ObjectDetails);
+        self.view_state.object_detail_list_state = ListState::default().with_selected(Some(0));
+        self.view_state.object_detail_path.clear();
+        self.view_state.object_detail_parent_list_states.clear();
+        let (_, field_paths) = self.produce_line_details_screen_content();
+        let Some(idx) = field_paths.iter().position(|e| *e == [field.as_str()]) else {
+            self.last_action_result = format!("no field `{field}` in line {}", target.line_nr);
+            return;
+        };
+        self.view_state.object_detail_list_state.select(Some(idx));
+        let field_path = field_paths[idx].clone();
+        self.view_state.selected_object_detail_field = Some(field_path.clone());
+        match self.selected_raw_line().and_then(|e| e.value_at(&field_path)) {
+            Some(serde_json::Value::Object(o)) if !o.is_empty() => self.enter_nested_object(field_path),
+            Some(serde_json::Value::Array(a)) if !a.is_empty() => self.enter_nested_object(field_path),
+            _ => self.switch_screen(Screen::ValueDetails),
+        }
+    }
+
+    /// selects the given raw line on the main screen – or the next visible one, if it is filtered out
+    pub fn select_raw_line(
+        &mut self,
+        line_idx: usize,
+    ) {
+        let pos = self
+            .visible_lines
+            .iter()
+            .position(|&e| e == line_idx)
+            .or_else(|| self.visible_lines.iter().position(|&e| e > line_idx));
+        if let Some(pos) = pos {
+            self.view_state.main_window_list_state.select(Some(pos));
+        }
+    }
+
+    /// the line selected on the main screen; None if there is none (e.g. it went away on a reload)
+    fn selected_raw_line(&self) -> Option<&RawJsonLine> { self.visible_line(self.view_state.main_window_list_state.selected()?) }
+
+    /// returns the full path of the selected field on the ObjectDetails screen
+    fn selected_field_path(&self) -> Option<Vec<String>> {
+        let field_path = self.view_state.selected_object_detail_field.as_ref()?;
+        let mut path = self.view_state.object_detail_path.clone();
+        path.extend(field_path.iter().cloned());
+        Some(path)
+    }
+
+    fn enter_nested_object(
+        &mut self,
+        path: Vec<String>,
+    ) {
+        self.close_find_task();
+        self.view_state.object_detail_path = path;
+        self.view_state
+            .object_detail_parent_list_states
+            .push(self.view_state.object_detail_list_state);
+        self.view_state.object_detail_list_state = ListState::default().with_selected(Some(0));
+    }
+
+    fn leave_nested_object(&mut self) {
+        self.close_find_task();
+        self.view_state.object_detail_path.pop();
+        self.view_state.object_detail_list_state = self.view_state.object_detail_parent_list_states.pop().unwrap_or_default();
+    }
+
+    pub fn with_search_hits_marked<'b>(
+        &self,
+        text: String,
+    ) -> Vec<Span<'b>> {
+        if let Some(t) = self.find_task.as_ref()
+            && !t.search_string.is_empty()
+        {
+            let mut i = 0;
+            let mut spans = vec![];
+
+            while let Some(hit) = t.find_in(&text[i..]) {
+                spans.push(Span::from(text[i..i + hit.start].to_string()));
+                spans.push(Span::from(text[i + hit.start..i + hit.end].to_string()).set_style(self.props.theme.search_hit_style));
+                i += hit.end;
+            }
+
+            if i < text.len() {
+                spans.push(Span::from(text[i..].to_string()));
+            }
+
+            spans
+        } else {
+            vec![Span::from(text)]
+        }
+    }
+
+    fn render_json_line<'x>(
+        &self,
+        m: &serde_json::Map<String, serde_json::Value>,
+    ) -> Line<'x> {
+        let render_property = |line: &mut Line, k: &str, v: &serde_json::Value| {
+            if line.iter().len() > 0 {
+                line.push_span(", ");
+            }
+            for e in self.with_search_hits_marked(k.to_owned()) {
+                line.push_span(e.bold());
+            }
+            line.push_span(":".to_owned());
+            for e in self.with_search_hits_marked(self.render_value(k, v)) {
+                line.push_span(self.with_value_style(e, v))
+            }
+        };
+
+        let mut line = Line::default();
+        let mut num_fields = 0;
+        let mut table_columns = None;
+        if self.view_state.main_screen_table_view {
+            table_columns = Some(self.render_table_columns(m));
+            num_fields = self.main_screen_fields_order().len();
+        } else {
+            // marks the fields scrolled off to the left
+            if self.line_rendering_field_offset > 0 {
+                line.push_span("…");
+            }
+            for k in self.main_screen_fields_order() {
+                if let Some(v) = m.get(k).filter(|&v| !self.is_suppressed_value(v)) {
+                    if self.line_rendering_field_offset <= num_fields {
+                        render_property(&mut line, k, v);
+                    }
+                    num_fields += 1;
+                }
+            }
+        }
+
+        for (k, v) in m {
+            if self.shows_unordered_field(k, v) {
+                if self.line_rendering_field_offset <= num_fields {
+                    render_property(&mut line, k, v);
+                }
+                num_fields += 1;
+            }
+        }
+
+        match table_columns {
+            Some(mut columns) => {
+                columns.extend(line.spans);
+                columns
+            }
+            None => line,
+        }
+    }
+
+    /// style of a line by its log level; None for the info level and for lines without a level
+    fn line_level_style(
+        &self,
+        raw_line: &RawJsonLine,
+    ) -> Option<Style> {
+        let Ok(serde_json::Value::Object(o)) = raw_line.json() else {
+            return None;
+        };
+        let level = match &self.level_field {
+            Some(field) => o.get(field),
+            None => o.get("level").or_else(|| o.get("severity")),
+        }?;
+
+        let theme = &self.props.theme;
+        match level {
+            serde_json::Value::String(s) => match s.to_ascii_uppercase().as_str() {
+                "FATAL" | "CRITICAL" | "CRIT" | "ERROR" | "ERR" | "PANIC" | "ALERT" | "EMERGENCY" => Some(theme.error_level_style),
+                "WARN" | "WARNING" => Some(theme.warn_level_style),
+                "DEBUG" | "TRACE" => Some(theme.debug_level_style),
+                _ => None,
+            },
+            // numeric levels (like bunyan or pino): 10 trace, 20 debug, 30 info, 40 warn, 50 error, 60 fatal
+            serde_json::Value::Number(n) => match n.as_u64()? {
+                50.. => Some(theme.error_level_style),
+                40..50 => Some(theme.warn_level_style),
+                ..30 => Some(theme.debug_level_style),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// renders a line of the Main screen – as a single row, or across multiple rows when pretty-printed
+    fn render_main_screen_rows<'x>(
+        &self,
+        raw_line: &RawJsonLine,
+    ) -> Vec<Line<'x>> {
+        let pretty_print = self.view_state.main_screen_pretty_print;
+        let mut rows = match raw_line.json() {
+            Ok(serde_json::Value::Object(o)) if pretty_print => self.render_pretty_json_object(o),
+            Ok(serde_json::Value::Object(o)) => vec![self.render_json_line(o)],
+            Ok(e) if pretty_print => serde_json::to_string_pretty(e)
+                .unwrap_or_default()
+                .lines()
+                .map(|e| self.render_pretty_row(e))
+                .collect(),
+            Ok(e) => vec![Line::from(self.with_search_hits_marked(format!("{e}")))],
+            Err(_) => {
+                let mut line = Line::from("⚠ invalid JSON: ");
+                line.extend(self.with_search_hits_marked(raw_line.content.clone()));
+                vec![line.red()]
+            }
+        };
+
+        // a line taller than the page would not be shown at all
+        let max_rows = cmp::max(self.page_len() as usize, 1);
+        if rows.len() > max_rows {
+            let num_hidden_rows = rows.len() - max_rows + 1;
+            rows.truncate(max_rows - 1);
+            rows.push(Line::from(format!("  … {num_hidden_rows} more rows")).dim());
+        }
+        rows
+    }
+
+    /// renders a JSON object like `serde_json::to_string_pretty` – with the fields in the same order
+    /// (and the same ones left out) as on a single line
+    fn render_pretty_json_object<'x>(
+        &self,
+        m: &serde_json::Map<String, serde_json::Value>,
+    ) -> Vec<Line<'x>> {
+        let ordered_fields = self.main_screen_fields_order().iter().filter_map(|k| m.get_key_value(k));
+        let other_fields = m.iter().filter(|&(k, v)| self.shows_unordered_field(k, v));
+        let fields: Vec<_> = ordered_fields
+            .chain(other_fields)
+            .filter(|&(_, v)| !self.is_suppressed_value(v))
+            .skip(self.line_rendering_field_offset)
+            .collect();
+
+        let mut lines = vec![Line::from(if self.line_rendering_field_offset > 0 { "{ …" } else { "{" })];
+        for (i, &(k, v)) in fields.iter().enumerate() {
+            let value = match v {
+                serde_json::Value::Array(_) | serde_json::Value::Object(_) => serde_json::to_string_pretty(v).unwrap_or_default(),
+                _ => self.render_value(k, v),
+            };
+            let num_rows = value.lines().count();
+            for (row_idx, row) in value.lines().enumerate() {
+                // the rows of a nested object or array are indented further
+                let indent = row.len() - row.trim_start_matches(' ').len();
+                let row = &row[indent..];
+                let mut line = Line::from(self.render_indent(2 + indent));
+                if row_idx == 0 {
+                    let key = serde_json::to_string(k).unwrap_or_default();
+                    line.extend(self.with_search_hits_marked(key).into_iter().map(|e| e.bold()));
+                    line.push_span(": ");
+                }
+                line.extend(
+                    self.with_search_hits_marked(row.to_string())
+                        .into_iter()
+                        .map(|e| self.with_value_style(e, v)),
+                );
+                if row_idx + 1 == num_rows && i + 1 < fields.len() {
+                    line.push_span(",");
+                }
+                lines.push(line);
+            }
+        }
+        lines.push(Line::from("}"));
+        lines
+    }
+
+    /// number of fields rendered for a JSON object on the Main screen – the unit of horizontal scrolling
+    fn num_rendered_fields(
+        &self,
+        m: &serde_json::Map<String, serde_json::Value>,
+    ) -> usize {
+        let num_ordered_fields = match self.view_state.main_screen_table_view {
+            true => self.main_screen_fields_order().len(),
+            false => self
+                .main_screen_fields_order()
+                .iter()
+                .filter(|&k| m.get(k).is_some_and(|v| !self.is_suppressed_value(v)))
+                .count(),
+        };
+        let num_other_fields = m.iter().filter(|&(k, v)| self.shows_unordered_field(k, v)).count();
+        num_ordered_fields + num_other_fields
+    }
+
+    /// the fields rendered in front on the Main screen (and as table columns) – the field selection (`--only`) or the ordered fields
+    fn main_screen_fields_order(&self) -> &[String] {
+        match self.field_selection_active() {
+            true => &self.field_selection,
+            false => self.props.active_fields_order(),
+        }
+    }
+
+    fn field_selection_active(&self) -> bool { !self.field_selection.is_empty() && !self.view_state.main_screen_all_fields }
+
+    /// whether a field, which is not in front, is rendered on the Main screen – never with a field selection (`--only`)
+    fn shows_unordered_field(
+        &self,
+        key: &str,
+        value: &serde_json::Value,
+    ) -> bool {
+        !self.field_selection_active()
+            && !self.props.active_fields_order().iter().any(|e| e == key)
+            && !self.props.active_fields_suppressed().iter().any(|e| e == key)
+            && !self.is_suppressed_value(value)
+    }
+
+    /// switches between the field selection (`--only`) and all fields on the Main screen
+    fn toggle_field_selection(&mut self) {
+        if self.field_selection.is_empty() {
+            self.last_action_result = "no field selection (--only)".to_string();
+            return;
+        }
+        self.view_state.main_screen_all_fields = !self.view_state.main_screen_all_fields;
+        self.line_rendering_field_offset = 0;
+        self.table_column_widths.borrow_mut().clear();
+        if self.main_screen_fields_order().is_empty() {
+            self.view_state.main_screen_table_view = false;
+        }
+        self.last_action_result = match self.view_state.main_screen_all_fields {
+            true => "all fields shown".to_string(),
+            false => format!("only {} shown", self.field_selection.join(", ")),
+        };
+    }
+
+    /// number of fields of the line selected on the Main screen; None if it's not a JSON object
+    fn selected_line_num_fields(&self) -> Option<usize> {
+        let pos = self.view_state.main_window_list_state.selected()?;
+        match self.visible_line(pos)?.json() {
+            Ok(serde_json::Value::Object(m)) => Some(self.num_rendered_fields(m)),
+            _ => None,
+        }
+    }
+
+    fn is_suppressed_value(
+        &self,
+        value: &serde_json::Value,
+    ) -> bool {
+        self.props.suppress_empty_fields && is_empty_value(value)
+    }
+
+    /// renders the values of the ordered fields as columns, padded to the column widths;
+    /// values wider than `MAX_TABLE_COLUMN_WIDTH` are cut
+    fn render_table_columns<'x>(
+        &self,
+        m: &serde_json::Map<String, serde_json::Value>,
+    ) -> Line<'x> {
+        let mut column_widths = self.table_column_widths.borrow_mut();
+        column_widths.resize(self.main_screen_fields_order().len(), 0);
+
+        let mut line = Line::default();
+        for (i, k) in self
+            .main_screen_fields_order()
+            .iter()
+            .enumerate()
+            .skip(self.line_rendering_field_offset)
+        {
+            let cell = match m.get(k) {
+                Some(v) => Line::from_iter(
+                    self.with_search_hits_marked(self.render_value(k, v))
+                        .into_iter()
+                        .map(|e| self.with_value_style(e, v)),
+                ),
+                None => Line::default(),
+            };
+            let width = &mut column_widths[i];
+            *width = cmp::max(*width, cmp::max(k.width(), cmp::min(cell.width(), MAX_TABLE_COLUMN_WIDTH)));
+            let cell = truncated(cell, *width);
+            let padding = *width - cell.width();
+            line.extend(cell.spans);
+            line.push_span(format!("{}{TABLE_COLUMN_GAP}", " ".repeat(padding)));
+        }
+        line
+    }
+
+    /// renders an abbreviated source name of the line, padded to the badge width and colored by the source;
+    /// None without `--show-source`
+    fn render_source_badge<'x>(
+        &self,
+        raw_line: &RawJsonLine,
+    ) -> Option<Span<'x>> {
+        let width = self.source_badge_width?;
+        let name = self
+            .raw_json_lines
+            .source_name(raw_line.source_id)
+            .map(|e| e.short_name())
+            .unwrap_or_default();
+        let name = match name.width() > width {
+            true => format!("{}…", name.chars().take(width.saturating_sub(1)).collect::<String>()),
+            false => name.to_string(),
+        };
+        let color = SOURCE_BADGE_COLORS[raw_line.source_id % SOURCE_BADGE_COLORS.len()];
+        Some(Span::styled(format!("{name:width$}"), Style::new().black().bg(color)))
+    }
+
+    /// renders the line number of the line – right-aligned and prefixed by the number of its source (`2:17`), if there are several;
+    /// None without `--line-numbers`
+    fn render_line_number<'x>(
+        &self,
+        raw_line: &RawJsonLine,
+    ) -> Option<Span<'x>> {
+        let width = self.line_number_width()?;
+        let line_number = match self.raw_json_lines.num_sources() > 1 {
+            true => format!("{}:{}", raw_line.source_id + 1, raw_line.line_nr),
+            false => raw_line.line_nr.to_string(),
+        };
+        Some(Span::raw(format!("{line_number:>width$}")).dim())
+    }
+
+    /// width of the line numbers – adapting to the largest one; None without `--line-numbers`
+    fn line_number_width(&self) -> Option<usize> {
+        if !self.line_numbers {
+            return None;
+        }
+        let num_digits = |n: usize| n.to_string().len();
+        let num_sources = self.raw_json_lines.num_sources();
+        match num_sources > 1 {
+            true => Some(num_digits(num_sources) + 1 + num_digits(self.max_line_nr)),
+            false => Some(num_digits(self.max_line_nr)),
+        }
+    }
+
+    /// header line of the table view with the names of the ordered fields; None if the table view is off
+    pub fn render_table_header(&self) -> Option<Line<'_>> {
+        if !self.view_state.main_screen_table_view {
+            return None;
+        }
+
+        let column_widths = self.table_column_widths.borrow();
+        let mut line = Line::from(" ".repeat(self.header_prefix_width()));
+        for (i, k) in self
+            .main_screen_fields_order()
+            .iter()
+            .enumerate()
+            .skip(self.line_rendering_field_offset)
+        {
+            let width = column_widths.get(i).copied().unwrap_or(0);
+            line.push_span(format!("{k:width$}{TABLE_COLUMN_GAP}").bold());
+        }
+        Some(line)
+    }
+
+    /// the field names above the lines on the Main screen (aligned with the columns in table view);
+    /// None if not pinned or without ordered fields
+    pub fn render_pinned_header(&self) -> Option<Line<'_>> {
+        if !self.pin_header {
+            return None;
+        }
+        if let Some(header) = self.render_table_header() {
+            return Some(header);
+        }
+
+        let fields: Vec<&str> = self
+            .main_screen_fields_order()
+            .iter()
+            .skip(self.line_rendering_field_offset)
+            .map(String::as_str)
+            .collect();
+        if fields.is_empty() {
+            return None;
+        }
+        Some(Line::from(vec![
+            " ".repeat(self.header_prefix_width()).into(),
+            fields.join(", ").into(),
+        ]))
+    }
+
+    /// leaves room for the highlight symbol, the bookmark marker, the line number and the source badge in front of the field names
+    fn header_prefix_width(&self) -> usize {
+        self.highlight_symbol_width()
+            + self.bookmark_gutter_width()
+            + self.line_number_width().map_or(0, |e| e + 1)
+            + self.source_badge_width.map_or(0, |e| e + 1)
+    }
+
+    /// width of the bookmark markers in front of the lines on the Main screen – none without bookmarks
+    fn bookmark_gutter_width(&self) -> usize { if self.bookmarks.is_empty() { 0 } else { 2 } }
+
+    /// the marker of a bookmarked line – or a blank for the other lines, as long as there are bookmarks
+    fn render_bookmark_marker(
+        &self,
+        line_idx: usize,
+    ) -> Option<Span<'static>> {
+        match (self.bookmarks.is_empty(), self.bookmarks.contains(&line_idx)) {
+            (true, _) => None,
+            (false, true) => Some(Span::styled("●", self.props.theme.bookmark_style)),
+            (false, false) => Some(Span::raw(" ")),
+        }
+    }
+
+    /// a marker for a line with duplicate keys on the Main screen – with `--warn-duplicate-keys` only
+    fn render_duplicate_keys_badge(
+        &self,
+        raw_line: &RawJsonLine,
+    ) -> Option<Span<'static>> {
+        if !self.warn_duplicate_keys || raw_line.duplicate_keys().is_empty() {
+            return None;
+        }
+        let keys: Vec<&str> = raw_line.duplicate_keys().iter().map(|e| e.path.as_str()).collect();
+        Some(Span::styled(
+            format!(" (duplicate {})", keys.join(", ")),
+            self.props.theme.warn_level_style,
+        ))
+    }
+
+    /// all values of the duplicate keys of the selected line for the ObjectDetails screen, e.g. `level: "info" | "warn"`
+    /// – with `--warn-duplicate-keys` only
+    pub fn render_duplicate_keys_title(&self) -> Option<Line<'static>> {
+        let raw_line = self.selected_raw_line()?;
+        if !self.warn_duplicate_keys || raw_line.duplicate_keys().is_empty() {
+            return None;
+        }
+        let keys: Vec<String> = raw_line
+            .duplicate_keys()
+            .iter()
+            .map(|e| format!("{}: {}", e.path, e.values.join(" | ")))
+            .collect();
+        Some(Line::styled(
+            format!(" duplicate keys – {} ", keys.join("; ")),
+            self.props.theme.warn_level_style,
+        ))
+    }
+
+    /// the path to the nested object on the ObjectDetails screen – array items by index, e.g. `items[1] › address`
+    pub fn render_object_detail_path(&self) -> String {
+        let mut rendered = String::new();
+        let mut value = self.selected_raw_line().and_then(|e| e.json().ok());
+        for segment in &self.view_state.object_detail_path {
+            value = match value {
+                Some(serde_json::Value::Array(a)) => {
+                    rendered.push_str(&format!("[{segment}]"));
+                    segment.parse::<usize>().ok().and_then(|e| a.get(e))
+                }
+                e => {
+                    if !rendered.is_empty() {
+                        rendered.push_str(" › ");
+                    }
+                    rendered.push_str(segment);
+                    e.and_then(|e| e.get(segment))
+                }
+            };
+        }
+        rendered
+    }
+
+    /// returns the ObjectDetails screen lines – with search hits marked and values colored – and field paths in rendered order
+    pub fn produce_line_details_screen_lines(&self) -> (Vec<Text<'_>>, Vec<Vec<String>>) {
+        let Some(raw_line) = self.selected_raw_line() else {
+            return (vec![Text::from(LINE_GONE_NOTE)], vec![]);
+        };
+        // without the border
+        let width = self.terminal_size.width.saturating_sub(2) as usize;
+        let Some(fields) = raw_line.fields_at(
+            self.props.active_fields_order(),
+            &self.view_state.object_detail_path,
+            self.props.suppress_empty_fields,
+            self.flatten,
+        ) else {
+            let line = Line::from(self.with_search_hits_marked(raw_line.content.clone()));
+            return (vec![Text::from(wrapped(line, width, 0))], vec![]);
+        };
+
+        fields
+            .into_iter()
+            .map(|field| {
+                let value = self.render_detail_value(&field);
+                let indent = field.label.width() + ATTRIBUTE_SEPARATOR.len();
+                let mut line = Line::from(self.with_search_hits_marked(field.label));
+                line.push_span(ATTRIBUTE_SEPARATOR);
+                for e in self.with_search_hits_marked(value) {
+                    line.push_span(self.with_value_style(e, field.value));
+                }
+                // suppressed fields are not shown on the Main screen
+                let suppressed = match (self.view_state.object_detail_path.is_empty(), field.path.as_slice()) {
+                    (true, [key]) => self.props.active_fields_suppressed().contains(key),
+                    _ => false,
+                };
+                if suppressed {
+                    line.push_span("  (suppressed)".italic());
+                    line = line.dim();
+                }
+                // long values continue indented under the value
+                (Text::from(wrapped(line, width, indent)), field.path)
+            })
+            .unzip()
+    }
+
+    /// returns the ObjectDetails screen lines as text and field paths in rendered order
+    pub fn produce_line_details_screen_content(&self) -> (Vec<String>, Vec<Vec<String>>) {
+        let Some(raw_line) = self.selected_raw_line() else {
+            return (vec![LINE_GONE_NOTE.to_string()], vec![]);
+        };
+        let Some(fields) = raw_line.fields_at(
+            self.props.active_fields_order(),
+            &self.view_state.object_detail_path,
+            self.props.suppress_empty_fields,
+            self.flatten,
+        ) else {
+            return (vec![raw_line.content.clone()], vec![]);
+        };
+
+        fields
+            .iter()
+            .map(|field| {
+                (
+                    format!("{}{ATTRIBUTE_SEPARATOR}{}", field.label, self.render_detail_value(field)),
+                    field.path.clone(),
+                )
+            })
+            .unzip()
+    }
+
+    /// renders a top level field value – the time field (`--time-field`) as formatted local time
+    fn render_value(
+        &self,
+        key: &str,
+        value: &serde_json::Value,
+    ) -> String {
+        if let Some(time_format) = &self.time_format
+            && time_format.field == key
+            && let Some(timestamp) = parse_timestamp(value)
+        {
+            return timestamp.with_timezone(&Local).format(&time_format.format).to_string();
+        }
+        format!("{value}")
+    }
+
+    /// renders a field value on the ObjectDetails screen
+    fn render_detail_value(
+        &self,
+        field: &Field,
+    ) -> String {
+        match (self.view_state.object_detail_path.is_empty(), field.path.as_slice()) {
+            (true, [key]) => self.render_value(key, field.value),
+            _ => format!("{}", field.value),
+        }
+    }
+
+    /// returns the text of the selected field value – or of the whole line on the RawLine screen;
+    /// a field, which went stale (e.g. the selected line changed), is rendered as a note instead
+    pub fn produce_value_details_screen_content(&self) -> String { self.produce_value_details().0 }
+
+    /// returns the ValueDetails (or RawLine) screen lines with search hits marked – and indent guides for pretty-printed JSON
+    fn produce_value_details_screen_lines(&self) -> Vec<Line<'_>> {
+        let (text, pretty_json) = self.produce_value_details();
+        text.lines()
+            .map(|e| match pretty_json {
+                true => self.render_pretty_row(e),
+                false => Line::from(self.with_search_hits_marked(e.to_string())),
+            })
+            .collect()
+    }
+
+    /// returns the rows of the ValueDetails (or RawLine) screen – its lines wrapped to the screen width, if wrapping is on;
+    /// the vertical scroll offset counts these rows
+    pub fn produce_value_details_screen_rows(&self) -> Vec<Line<'_>> {
+        let lines = self.produce_value_details_screen_lines();
+        match self.view_state.value_screen_wrap {
+            true => lines.into_iter().flat_map(|e| wrapped(e, self.value_screen_width(), 0)).collect(),
+            false => lines,
+        }
+    }
+
+    /// width of the ValueDetails screen text – without the border
+    fn value_screen_width(&self) -> usize { self.terminal_size.width.saturating_sub(2) as usize }
+
+    /// number of rows per line of the ValueDetails (or RawLine) screen (see [Self::produce_value_details_screen_rows])
+    fn value_screen_line_heights(&self) -> Vec<usize> {
+        let lines = self.produce_value_details_screen_lines();
+        match self.view_state.value_screen_wrap {
+            true => lines.into_iter().map(|e| wrapped(e, self.value_screen_width(), 0).len()).collect(),
+            false => vec![1; lines.len()],
+        }
+    }
+
+    /// first row of the given line on the ValueDetails (or RawLine) screen
+    fn value_screen_row_of_line(
+        &self,
+        line_idx: usize,
+    ) -> u16 {
+        let row: usize = self.value_screen_line_heights().into_iter().take(line_idx).sum();
+        u16::try_from(row).unwrap_or(u16::MAX)
+    }
+
+    /// line shown in the given row of the ValueDetails (or RawLine) screen
+    fn value_screen_line_of_row(
+        &self,
+        row: u16,
+    ) -> usize {
+        let heights = self.value_screen_line_heights();
+        let mut remaining_rows = row as usize;
+        for (idx, &height) in heights.iter().enumerate() {
+            match remaining_rows.checked_sub(height) {
+                Some(e) => remaining_rows = e,
+                None => return idx,
+            }
+        }
+        heights.len().saturating_sub(1)
+    }
+
+    /// the value details text (see [Self::produce_value_details_screen_content]) and whether it is pretty-printed JSON
+    fn produce_value_details(&self) -> (String, bool) { self.produce_value_details_folded(self.view_state.value_screen_fold_level) }
+
+    /// the value details text with the objects and arrays beyond the fold level collapsed
+    fn produce_value_details_folded(
+        &self,
+        fold_level: Option<usize>,
+    ) -> (String, bool) {
+        let Some(selected_line) = self.selected_raw_line() else {
+            return (LINE_GONE_NOTE.into(), false);
+        };
+        if self.active_screen == Screen::RawLine {
+            return (selected_line.content.clone(), false);
+        }
+        let Some(value) = self.value_details_value() else {
+            return ("(field no longer present – `Esc` goes back)".into(), false);
+        };
+        match value.as_ref() {
+            serde_json::Value::String(s) => (s.clone(), false),
+            e @ (serde_json::Value::Object(_) | serde_json::Value::Array(_)) if self.view_state.value_screen_pretty_print => {
+                match fold_level {
+                    Some(level) => (to_string_pretty_folded(e, level), true),
+                    None => (serde_json::to_string_pretty(e).expect("json value should be serializable"), true),
+                }
+            }
+            e => (format!("{e}"), false),
+        }
+    }
+
+    /// the value of the selected field – decoded, if it is embedded JSON and decoding is on; None for a stale field
+    fn value_details_value(&self) -> Option<Cow<'_, serde_json::Value>> {
+        let selected_line = self.visible_line(self.view_state.main_window_list_state.selected()?)?;
+        let field_value = selected_line.value_at(&self.selected_field_path()?)?;
+        let embedded_json = match field_value {
+            serde_json::Value::String(s) if self.view_state.value_screen_decode_json => embedded_json(s),
+            _ => None,
+        };
+        Some(embedded_json.map_or(Cow::Borrowed(field_value), Cow::Owned))
+    }
+
+    /// collapses the objects and arrays of the deepest expanded nesting level of the pretty-printed value – or expands them again
+    fn fold_value_details(
+        &mut self,
+        fold: bool,
+    ) {
+        if !self.view_state.value_screen_pretty_print {
+            return;
+        }
+        let Some(depth) = self.value_details_value().map(|e| json_depth(&e)) else {
+            return;
+        };
+        // the top level stays expanded
+        let level = cmp::min(self.view_state.value_screen_fold_level.unwrap_or(depth), depth);
+        let level = match fold {
+            true => cmp::max(level.saturating_sub(1), 1),
+            false => level + 1,
+        };
+        self.view_state.value_screen_fold_level = (level < depth).then_some(level);
+    }
+
+    /// renders a row of pretty-printed JSON with search hits marked – indented by indent guides with `--indent-guides`
+    fn render_pretty_row<'b>(
+        &self,
+        row: &str,
+    ) -> Line<'b> {
+        let indent = row.len() - row.trim_start_matches(' ').len();
+        let mut line = Line::from(self.render_indent(indent));
+        line.extend(self.with_search_hits_marked(row[indent..].to_string()));
+        line
+    }
+
+    /// the indentation of a pretty-printed JSON row – with `--indent-guides` a dimmed `│` per level (of two columns)
+    fn render_indent(
+        &self,
+        width: usize,
+    ) -> Span<'static> {
+        match self.indent_guides {
+            true => Span::raw(format!("{}{}", "│ ".repeat(width / 2), " ".repeat(width % 2))).dim(),
+            false => Span::raw(" ".repeat(width)),
+        }
+    }
+
+    /// title of the ValueDetails (or RawLine) screen – telling about an embedded JSON document in a string value
+    pub fn render_value_details_title(&self) -> Option<String> {
+        if self.active_screen == Screen::RawLine {
+            return Some("raw line".into());
+        }
+        let field_path = self.selected_field_path()?;
+        let selected_line = self.visible_line(self.view_state.main_window_list_state.selected()?)?;
+        let embedded = match selected_line.value_at(&field_path)? {
+            serde_json::Value::String(s) if embedded_json(s).is_some() => match self.view_state.value_screen_decode_json {
+                true => Some("embedded JSON (decoded)"),
+                false => Some("embedded JSON – `d` decodes it"),
+            },
+            _ => None,
+        };
+        let folded = self
+            .view_state
+            .value_screen_fold_level
+            .filter(|_| self.view_state.value_screen_pretty_print)
+            .map(|e| format!("folded to level {e}"));
+        match (embedded, folded) {
+            (Some(embedded), Some(folded)) => Some(format!("{embedded} · {folded}")),
+            (embedded, folded) => embedded.map(str::to_string).or(folded),
+        }
+    }
+
+    /// renders the origin of the selected line and its position, e.g. `app.json:17 [1234 / 98765]`;
+    /// with active line filters the position is given among the matching lines: `[12 / 340 of 98765]`;
+    /// on the Main screen followed by the first shown field among the fields of the line, e.g. `3/8 fields`;
+    /// in tabs mode led by the shown tab, e.g. `tab 2/3 · `
+    pub fn render_status_line_left(&self) -> String {
+        let Some(pos) = self.view_state.main_window_list_state.selected() else {
+            return "".into();
+        };
+
+        let Some(raw_line) = self.visible_line(pos) else {
+            return "".into();
+        };
+
+        let source_name = self.raw_json_lines.source_name(raw_line.source_id).expect("invalid source id");
+
+        let num_visible_lines = self.num_visible_lines();
+        let num_lines = self.raw_json_lines.lines.len();
+        let position = if num_visible_lines == num_lines {
+            format!("[{} / {}]", pos + 1, num_lines)
+        } else {
+            format!("[{} / {} of {}]", pos + 1, num_visible_lines, num_lines)
+        };
+
+        // the horizontal scroll position among the fields of the selected line
+        let fields = match self.selected_line_num_fields() {
+            Some(num_fields) if self.active_screen == Screen::Main && num_fields > 0 => {
+                format!(" {}/{} fields", self.line_rendering_field_offset + 1, num_fields)
+            }
+            _ => String::new(),
+        };
+
+        let tab = match self.tabs {
+            true => format!("tab {}/{} · ", self.active_tab + 1, self.raw_json_lines.num_sources()),
+            false => String::new(),
+        };
+
+        format!("{tab}{}:{} {}{}", source_name, raw_line.line_nr, position, fields)
+    }
+
+    /// the last action result – followed by e.g. `12:34:56 · 12.3k lines · 4.5 MB`, if the clock is shown
+    pub fn render_status_line_right(&self) -> String {
+        if !self.clock {
+            return self.last_action_result.clone();
+        }
+        format!(
+            "{}{} · {} lines · {:.1} MB",
+            match self.last_action_result.is_empty() {
+                true => String::new(),
+                false => format!("{}  ", self.last_action_result),
+            },
+            Local::now().format("%H:%M:%S"),
+            format_count(self.raw_json_lines.lines.len()),
+            self.num_loaded_bytes as f64 / 1_000_000.0
+        )
+    }
+
+    pub fn render_find_task_line_left(&self) -> Line<'_> {
+        let Some(task) = &self.find_task else {
+            return "".into();
+        };
+
+        let color = match task.found {
+            None => Color::default(),
+            Some(false) => self.props.theme.miss_color,
+            Some(true) => self.props.theme.match_color,
+        };
+
+        let mut line = " ["
+            .to_span()
+            .set_style(color)
+            .add("Find ".to_span())
+            .add("🔍".to_span())
+            .add(Span::raw(if task.regex_mode { " /re/" } else { "" }))
+            .add(Span::raw(if task.fuzzy_mode { " ~fuzzy~" } else { "" }))
+            .add(Span::raw(if task.rendered_mode { " (as shown)" } else { "" }))
+            .add(Span::raw(if task.highlight_only { " (highlight)" } else { "" }))
+            .add(Span::raw(if task.case_insensitive { " (i)" } else { "" }))
+            .add(": ".bold());
+        line.extend(task.render_search_string());
+        line.push_span("  ] ".to_span().set_style(color));
+        line
+    }
+
+    pub fn render_filter_task_line_left(&self) -> Line<'_> {
+        let Some(task) = &self.filter_task else {
+            return "".into();
+        };
+
+        let color = match (task.filter_string.is_empty(), self.interactive_filter.is_some()) {
+            (true, _) => Color::default(),
+            (false, false) => self.props.theme.miss_color,
+            (false, true) => self.props.theme.match_color,
+        };
+
+        " [".to_span()
+            .set_style(color)
+            .add("Filter (key=value | key~substring | key>number)".to_span())
+            .add(": ".bold())
+            .add(task.filter_string.to_span().bold())
+            .add("  ] ".to_span().set_style(color))
+            .to_owned()
+    }
+
+    pub fn render_filter_task_line_right(&self) -> Line<'_> {
+        format!("{} / {} lines", self.num_visible_lines(), self.raw_json_lines.lines.len()).into()
+    }
+
+    pub fn render_export_task_line_left(&self) -> Line<'_> {
+        let Some(task) = &self.export_task else {
+            return "".into();
+        };
+
+        " [".to_span()
+            .add("Export to file".to_span())
+            .add(": ".bold())
+            .add(task.file_name.to_span().bold())
+            .add("  ] ".to_span())
+            .to_owned()
+    }
+
+    pub fn render_export_task_line_right(&self) -> Line<'_> { format!("{} lines", self.num_visible_lines()).into() }
+
+    pub fn render_open_file_task_line_left(&self) -> Line<'_> {
+        let Some(task) = &self.open_file_task else {
+            return "".into();
+        };
+
+        let title = match task.append {
+            true => "Append file",
+            false => "Open file",
+        };
+        " [".to_span()
+            .add(Span::raw(title))
+            .add(": ".bold())
+            .add(task.path.to_span().bold())
+            .add("  ] ".to_span())
+            .to_owned()
+    }
+
+    pub fn render_projection_task_line_left(&self) -> Line<'_> {
+        let Some(task) = &self.projection_task else {
+            return "".into();
+        };
+
+        " [".to_span()
+            .add("Project field (JSON Pointer)".to_span())
+            .add(": ".bold())
+            .add(task.pointer.to_span().bold())
+            .add("  ] ".to_span())
+            .to_owned()
+    }
+
+    pub fn render_find_task_line_right(&self) -> Line<'_> {
+        let Some(task) = &self.find_task else {
+            return "".into();
+        };
+
+        let Some(found) = task.found else {
+            return "".into();
+        };
+
+        match (found, task.match_ordinal, &task.match_indices) {
+            (true, Some(ordinal), Some(indices)) => format!("{ordinal} / {}", indices.len()).into(),
+            // highlight-only mode, with the selected line not being a match
+            (true, None, Some(indices)) => format!("{} found", indices.len()).into(),
+            (true, _, _) => "found".into(),
+            (false, _, _) => "NOT found".into(),
+        }
     }
 
-    pub fn with_search_hits_marked<'b>(
-        &self,
-        text: String,
-    ) -> Vec<Span<'b>> {
-        if let Some(t) = self.find_task.as_ref() && !t.search_string.is_empty(){
-            let mut i = 0;
-            let mut spans = vec![];
+    pub fn page_len(&self) -> u16 { self.terminal_size.height.saturating_sub(2 + self.pinned_header_height()) }
 
-            while let Some(hit) = text[i..].find(&t.search_string) {
-                spans.push(Span::from(text[i..i+hit].to_string()));
-                spans.push(Span::from(text[i+hit..i+hit+t.search_string.len()].to_string()).set_style(Self::find_matches_style()));
-                i = i+hit+t.search_string.len();
-            }
+    /// rows of the pinned header on the active screen
+    fn pinned_header_height(&self) -> u16 { u16::from(self.active_screen == Screen::Main && self.render_pinned_header().is_some()) }
 
-            if i < text.len() {
-                spans.push(Span::from(text[i..].to_string()));
-            }
+    /// available width for a line on the Main screen – without the border and the highlight symbol
+    fn main_screen_line_width(&self) -> usize { (self.terminal_size.width as usize).saturating_sub(2 + self.highlight_symbol_width()) }
 
-            spans
-        } else {
-            vec![Span::from(text)]
+    fn highlight_symbol_width(&self) -> usize { self.props.theme.highlight_symbol.width() }
+
+    /// number of lines to scroll for a (half) page up/down message;
+    /// a page overlaps the previous one by a line (like in `less`), so the last line of a page becomes the first of the next
+    fn page_scroll_len(
+        &self,
+        msg: Message,
+    ) -> u16 {
+        match msg {
+            Message::HalfPageUp | Message::HalfPageDown => cmp::max(self.page_len() / 2, 1),
+            _ => cmp::max(self.page_len().saturating_sub(1), 1),
         }
     }
 
-    fn render_json_line<'x>(
+    /// number of lines to move the selection on the Main (or Projection) screen for a (half) page up/down message;
+    /// pretty-printed lines take several rows each, so only those fitting into the page are passed
+    fn page_scroll_items(
         &self,
-        m: &serde_json::Map<String, serde_json::Value>,
-    ) -> Line<'x> {
-        let render_property = |line: &mut Line, k: &str, v: &serde_json::Value| {
-            if line.iter().len() > 0 {
-                line.push_span(", ");
-            }
-            for e in self.with_search_hits_marked(k.to_owned()) {
-                line.push_span(e.bold());
-            }
-            line.push_span(":".to_owned());
-            for e in self.with_search_hits_marked(format!("{v}")) {
-                line.push_span(e)
-            }
-        };
-
-        let mut line = Line::default();
-        let mut num_fields = 0;
-        for k in &self.props.fields_order {
-            if let Some(v) = m.get(k) {
-                if self.line_rendering_field_offset <= num_fields {
-                    render_property(&mut line, k, v);
-                }
-                num_fields += 1;
-            }
+        pos: usize,
+        msg: Message,
+    ) -> usize {
+        let page_rows = self.page_scroll_len(msg) as usize;
+        if !(self.active_screen == Screen::Main && self.view_state.main_screen_pretty_print) {
+            return page_rows;
         }
 
-        for (k, v) in m {
-            if !self.props.fields_order.contains(k) && !self.props.fields_suppressed.contains(k) {
-                if self.line_rendering_field_offset <= num_fields {
-                    render_property(&mut line, k, v);
-                }
-                num_fields += 1;
+        let down = matches!(msg, Message::PageDown | Message::HalfPageDown);
+        let neighbour = |n: usize| match down {
+            true => Some(pos + n).filter(|&e| e < self.num_visible_lines()),
+            false => pos.checked_sub(n),
+        };
+        let mut remaining_rows = page_rows;
+        let mut num_items = 0;
+        while let Some(next) = neighbour(num_items + 1) {
+            let height = self.main_screen_item_height(next);
+            if height > remaining_rows && num_items > 0 {
+                break;
             }
+            remaining_rows = remaining_rows.saturating_sub(height);
+            num_items += 1;
         }
+        cmp::max(num_items, 1)
+    }
 
-        if num_fields > self.num_fields_high_water_mark.get() {
-            self.num_fields_high_water_mark.replace(num_fields);
+    /// number of rows the line at the given position takes on the Main screen
+    fn main_screen_item_height(
+        &self,
+        pos: usize,
+    ) -> usize {
+        match (self.view_state.main_screen_pretty_print, self.visible_line(pos)) {
+            (true, Some(line)) => self.render_main_screen_rows(line).len(),
+            _ => 1,
         }
-
-        line
     }
 
-    /// returns JSON object lines and keys in rendered order
-    pub fn produce_line_details_screen_content(&self) -> (Vec<String>, Vec<String>) {
-        let line_idx = self
-            .view_state
-            .main_window_list_state
-            .selected()
-            .expect("we should find a a selected line");
-        self.raw_json_lines.lines[line_idx].produce_rendered_fields_as_list(&self.props.fields_order)
+    fn save_settings(&mut self) {
+        self.last_action_result = match self.props.save() {
+            Ok(_) => {
+                self.settings_changed = false;
+                "Ok: settings saved".to_string()
+            }
+            Err(_) => "Error: failed to save settings".to_string(),
+        };
     }
 
-    pub fn render_status_line_left(&self) -> String {
-        let Some(line_nr) = self.view_state.main_window_list_state.selected() else {
-            return "".into();
+    /// loads the lines of another file – replacing the loaded lines (starting over at the first line) or appended to them
+    fn open_file(
+        &mut self,
+        path: &Path,
+        append: bool,
+    ) {
+        let progress = LoadProgress::new(1);
+        let lines = match crate::load::load_file(path, None, None, self.csv_numbers, &progress) {
+            Ok(e) => e,
+            Err(e) => {
+                self.last_action_result = format!("Error: {e:#}");
+                return;
+            }
         };
+        let num_lines = lines.lines.len();
+        self.replace_lines(lines, append);
+        if !append {
+            self.opened_files.clear();
+            self.input_files_replaced = true;
+        }
+        self.opened_files.push(path.to_path_buf());
 
-        let Some(raw_line) = self.raw_json_lines.lines.get(line_nr) else {
-            return "".into();
+        self.last_action_result = match progress.take_notes().into_iter().next() {
+            Some(note) => note,
+            None => format!("Ok: {num_lines} lines loaded from {}", path.to_string_lossy()),
         };
+    }
 
-        let source_name = self.raw_json_lines.source_name(raw_line.source_id).expect("invalid source id");
+    /// loads the opened files again – in the order they were opened
+    fn load_opened_files(
+        &self,
+        progress: &LoadProgress,
+    ) -> anyhow::Result<RawJsonLines> {
+        let mut lines = RawJsonLines::default();
+        for path in &self.opened_files {
+            lines.append(crate::load::load_file(path, None, None, self.csv_numbers, progress)?);
+        }
+        Ok(lines)
+    }
 
-        format!("{}:{}", source_name, raw_line.line_nr)
+    /// replaces the loaded lines (starting over at the first line) or appends to them
+    fn replace_lines(
+        &mut self,
+        lines: RawJsonLines,
+        append: bool,
+    ) {
+        if !append {
+            self.raw_json_lines = RawJsonLines::default();
+            self.bookmarks.clear();
+            self.active_tab = 0;
+            self.tab_selections.clear();
+            self.view_state.main_window_list_state.select(Some(0));
+            self.view_state.object_detail_list_states_by_line.clear();
+            self.line_rendering_field_offset = 0;
+            self.table_column_widths.borrow_mut().clear();
+        }
+        self.raw_json_lines.append(lines);
+        self.count_loaded_lines();
+        self.apply_line_filters();
+        if let Some(task) = self.find_task.as_mut() {
+            task.match_indices = None;
+        }
     }
 
-    pub fn render_status_line_right(&self) -> String { self.last_action_result.clone() }
+    /// writes the visible lines (those passing the line filters) to a file – in the export format;
+    /// CSV columns are the fields in front on the Main screen
+    fn export_visible_lines(
+        &mut self,
+        path: &Path,
+    ) {
+        let write_lines = || -> io::Result<()> {
+            let file = io::BufWriter::new(File::create(path)?);
+            let lines = (0..self.num_visible_lines()).map(|pos| self.visible_line(pos).unwrap());
+            self.export_format.write(file, lines, self.main_screen_fields_order())
+        };
 
-    pub fn render_find_task_line_left(&self) -> Line {
-        let Some(task) = &self.find_task else {
-            return "".into();
+        self.last_action_result = match write_lines() {
+            Ok(_) => format!("Ok: {} lines written to {}", self.num_visible_lines(), path.display()),
+            Err(e) => format!("Error: failed to write {}: {e}", path.display()),
         };
+    }
 
-        let color = match task.found {
-            None => Color::default(),
-            Some(false) => Color::Red,
-            Some(true) => Color::Green,
+    /// copies the text of the selected field value to the clipboard – with all nested structures expanded
+    fn copy_value(&mut self) {
+        let (text, _) = self.produce_value_details_folded(None);
+        self.last_action_result = match clipboard::copy(&text) {
+            Ok(_) => format!("Ok: copied {} chars", text.chars().count()),
+            Err(e) => format!("Error: {e}"),
         };
+    }
 
-        " [".to_span()
-            .set_style(color)
-            .add("Find ".to_span())
-            .add("🔍".to_span())
-            .add(": ".bold())
-            .add(task.search_string.to_span().bold())
-            .add("  ] ".to_span().set_style(color))
-            .to_owned()
+    /// a key without a function on the active screen is pointed out on the status line, while mouse events are just ignored
+    fn not_handled(
+        mut self,
+        msg: Message,
+    ) -> (Model, Option<Message>) {
+        if !matches!(msg, Message::MouseClick { .. } | Message::Resized(_)) {
+            self.last_action_result = KEY_WITHOUT_FUNCTION.to_string();
+        }
+        (self, None)
     }
 
-    pub fn render_find_task_line_right(&self) -> Line {
-        let Some(task) = &self.find_task else {
-            return "".into();
+    /// closes the find dialog – remembering its search string in the find history
+    fn close_find_task(&mut self) {
+        let Some(task) = self.find_task.take() else {
+            return;
         };
+        if task.search_string.is_empty() {
+            return;
+        }
+        self.find_history.retain(|e| e != &task.search_string);
+        self.find_history.push(task.search_string);
+        let num_dropped = self.find_history.len().saturating_sub(MAX_FIND_HISTORY);
+        self.find_history.drain(..num_dropped);
+    }
 
-        let Some(found) = task.found else {
-            return "".into();
-        };
+    pub fn find_history(&self) -> &[String] { &self.find_history }
 
-        match found {
-            true => "found".into(),
-            false => "NOT found".into(),
-        }
+    /// sets the find history – e.g. that of the last sessions; oldest first
+    pub fn set_find_history(
+        &mut self,
+        mut history: Vec<String>,
+    ) {
+        let num_dropped = history.len().saturating_sub(MAX_FIND_HISTORY);
+        history.drain(..num_dropped);
+        self.find_history = history;
     }
 
-    pub fn page_len(&self) -> u16 { self.terminal_size.height.saturating_sub(2) }
+    /// `up` recalls an older search string, when the search string is empty or recalled already; `down` a newer one
+    fn browses_find_history(
+        &self,
+        older: bool,
+    ) -> bool {
+        let task = self.find_task.as_ref().expect("find task should be set");
+        match task.history_pos {
+            Some(_) => true,
+            None => older && task.search_string.is_empty() && !self.find_history.is_empty(),
+        }
+    }
 
-    fn save_settings(&mut self) {
-        self.last_action_result = match self.props.save() {
-            Ok(_) => "Ok: settings saved".to_string(),
-            Err(_) => "Error: failed to save settings".to_string(),
+    /// puts the older (or newer) search string of the find history into the find dialog;
+    /// going past the newest one empties the search string
+    fn recall_search(
+        &mut self,
+        older: bool,
+    ) {
+        let task = self.find_task.as_mut().expect("find task should be set");
+        let pos = match (task.history_pos, older) {
+            (None, _) => Some(self.find_history.len() - 1),
+            (Some(pos), true) => Some(pos.saturating_sub(1)),
+            (Some(pos), false) => Some(pos + 1).filter(|&e| e < self.find_history.len()),
         };
+        task.search_string = pos.map(|e| self.find_history[e].clone()).unwrap_or_default();
+        task.search_criteria_changed();
+        task.history_pos = pos;
+        self.find_as_you_type();
+    }
+
+    /// after a change of the search criteria: jumps to the first match from the selected line on (the best match in fuzzy mode) –
+    /// or just counts the matches (which are highlighted anyway) in highlight-only mode
+    fn find_as_you_type(&mut self) {
+        if self.find_task.as_ref().is_some_and(|e| e.fuzzy_mode) {
+            return self.find_best_fuzzy_match();
+        }
+        if !self.find_task.as_ref().is_some_and(|e| e.highlight_only) {
+            return self.find_next(false);
+        }
+        let mut find_task = self.find_task.take().expect("find task should be set");
+        self.update_match_position(&mut find_task);
+        find_task.found = find_task.match_indices.as_ref().map(|e| !e.is_empty());
+        self.find_task = Some(find_task);
     }
 
     fn find_next(
         &mut self,
         skip_current_line: bool,
     ) {
-        let mut find_task = self.find_task.clone().expect("find task should be set");
+        self.find(SearchDirection::Forward, skip_current_line)
+    }
+
+    fn find_previous(
+        &mut self,
+        skip_current_line: bool,
+    ) {
+        self.find(SearchDirection::Backward, skip_current_line)
+    }
+
+    /// selects the next line matching the find task in the given direction – starting at the selected line
+    fn find(
+        &mut self,
+        direction: SearchDirection,
+        skip_current_line: bool,
+    ) {
+        let mut find_task = self.find_task.take().expect("find task should be set");
         if find_task.found.is_none() {
             find_task.found = Some(false);
         };
 
         match self.active_screen {
-            Screen::Done => (),
-            Screen::Main => {
-                let mut start_line_num = self
-                    .view_state
-                    .main_window_list_state
-                    .selected()
-                    .unwrap_or(self.view_state.main_window_list_state.offset());
-                if skip_current_line {
-                    start_line_num += 1
+            Screen::Done | Screen::Loading => (),
+            Screen::Histogram => {
+                let selected = self.view_state.histogram_list_state.selected();
+                let lines = self.produce_histogram_screen_content();
+                let found = direction
+                    .search_order(selected, lines.len(), skip_current_line)
+                    .find(|&idx| find_task.matches(&lines[idx]));
+                if let Some(idx) = found {
+                    find_task.found = Some(true);
+                    self.view_state.histogram_list_state.select(Some(idx));
                 }
-                for (idx, line) in self.raw_json_lines.lines[start_line_num..].iter().enumerate() {
-                    if line.content.contains(&find_task.search_string) {
-                        find_task.found = Some(true);
-                        self.view_state.main_window_list_state.select(Some(start_line_num + idx));
-                        break;
-                    }
+            }
+            Screen::Projection => {
+                let selected = self.view_state.main_window_list_state.selected();
+                let lines = self.produce_projection_screen_content();
+                let found = direction
+                    .search_order(selected, lines.len(), skip_current_line)
+                    .find(|&pos| find_task.matches(&lines[pos]));
+                if let Some(pos) = found {
+                    find_task.found = Some(true);
+                    self.view_state.main_window_list_state.select(Some(pos));
+                }
+            }
+            Screen::Main => {
+                let selected = self.view_state.main_window_list_state.selected();
+                let found = direction
+                    .search_order(selected, self.num_visible_lines(), skip_current_line)
+                    .find(|&pos| self.main_screen_line_matches(&find_task, self.visible_line(pos).unwrap()));
+                if let Some(pos) = found {
+                    find_task.found = Some(true);
+                    self.view_state.main_window_list_state.select(Some(pos));
                 }
             }
             Screen::ObjectDetails => {
-                let mut start_line_num = self
-                    .view_state
-                    .object_detail_list_state
-                    .selected()
-                    .unwrap_or(self.view_state.object_detail_list_state.offset());
-                if skip_current_line {
-                    start_line_num += 1
+                let selected = self.view_state.object_detail_list_state.selected();
+                let (lines, field_paths) = self.produce_line_details_screen_content();
+                let found = direction
+                    .search_order(selected, lines.len(), skip_current_line)
+                    .find(|&idx| find_task.matches(&lines[idx]));
+                if let Some(idx) = found {
+                    find_task.found = Some(true);
+                    self.view_state.object_detail_list_state.select(Some(idx));
+                    self.view_state.selected_object_detail_field = field_paths.get(idx).cloned();
                 }
-                let (lines, field_names) = self.produce_line_details_screen_content();
-                for (idx, line) in lines[start_line_num..].iter().enumerate() {
-                    if line.contains(&find_task.search_string) {
-                        find_task.found = Some(true);
-                        self.view_state.object_detail_list_state.select(Some(start_line_num + idx));
-                        let selected_field_name = field_names[start_line_num + idx].clone();
-                        self.view_state.selected_object_detail_field_name = Some(selected_field_name);
-                        break;
-                    }
+            }
+            Screen::ValueDetails | Screen::RawLine => {
+                let text = self.produce_value_details_screen_content();
+                let lines: Vec<&str> = text.lines().collect();
+                let found = direction
+                    .search_order(Some(self.view_state.value_screen_find_line), lines.len(), skip_current_line)
+                    .find(|&idx| find_task.matches(lines[idx]));
+                if let Some(idx) = found {
+                    find_task.found = Some(true);
+                    self.view_state.value_screen_find_line = idx;
+                    self.view_state.value_screen_vertical_scroll_offset = self.value_screen_row_of_line(idx);
                 }
             }
-            Screen::ValueDetails => {}
         };
 
+        self.update_match_position(&mut find_task);
         self.find_task = Some(find_task);
     }
 
-    fn find_previous(&mut self) {
-        let mut find_task = self.find_task.clone().expect("find task should be set");
-        if find_task.found.is_none() {
-            find_task.found = Some(false);
+    /// selects the best fuzzy match on the active screen – the first one of equally good matches
+    fn find_best_fuzzy_match(&mut self) {
+        let mut find_task = self.find_task.take().expect("find task should be set");
+        // an empty search string matches everything equally – the selection stays
+        if find_task.search_string.is_empty() {
+            self.update_match_position(&mut find_task);
+            self.find_task = Some(find_task);
+            return;
+        }
+        let best = |texts: &mut dyn Iterator<Item = &str>| {
+            texts
+                .enumerate()
+                .filter_map(|(idx, text)| find_task.fuzzy_score(text).map(|score| (idx, score)))
+                .max_by_key(|&(idx, score)| (score, cmp::Reverse(idx)))
+                .map(|(idx, _)| idx)
         };
 
-        match self.active_screen {
-            Screen::Done => {}
+        let found = match self.active_screen {
+            Screen::Done | Screen::Loading => None,
+            Screen::Histogram => {
+                let idx = best(&mut self.produce_histogram_screen_content().iter().map(String::as_str));
+                self.view_state
+                    .histogram_list_state
+                    .select(idx.or(self.view_state.histogram_list_state.selected()));
+                idx
+            }
+            Screen::Projection => {
+                let pos = best(&mut self.produce_projection_screen_content().iter().map(String::as_str));
+                self.view_state
+                    .main_window_list_state
+                    .select(pos.or(self.view_state.main_window_list_state.selected()));
+                pos
+            }
+            Screen::Main if find_task.rendered_mode => {
+                let texts: Vec<String> = (0..self.num_visible_lines())
+                    .map(|pos| self.render_main_screen_text(self.visible_line(pos).unwrap()))
+                    .collect();
+                let pos = best(&mut texts.iter().map(String::as_str));
+                self.view_state
+                    .main_window_list_state
+                    .select(pos.or(self.view_state.main_window_list_state.selected()));
+                pos
+            }
             Screen::Main => {
-                let start_line_num = self
-                    .view_state
+                let pos = best(&mut (0..self.num_visible_lines()).map(|pos| self.visible_line(pos).unwrap().content.as_str()));
+                self.view_state
                     .main_window_list_state
-                    .selected()
-                    .unwrap_or(self.view_state.main_window_list_state.offset());
-                for (idx, line) in self.raw_json_lines.lines[..start_line_num].iter().rev().enumerate() {
-                    if line.content.contains(&find_task.search_string) {
-                        find_task.found = Some(true);
-                        self.view_state.main_window_list_state.select(Some(start_line_num - 1 - idx));
-                        break;
-                    }
-                }
+                    .select(pos.or(self.view_state.main_window_list_state.selected()));
+                pos
             }
             Screen::ObjectDetails => {
-                let start_line_num = self
-                    .view_state
-                    .object_detail_list_state
-                    .selected()
-                    .unwrap_or(self.view_state.object_detail_list_state.offset());
-                let (lines, field_names) = self.produce_line_details_screen_content();
-                for (idx, line) in lines[..start_line_num].iter().rev().enumerate() {
-                    if line.contains(&find_task.search_string) {
-                        find_task.found = Some(true);
-                        self.view_state.object_detail_list_state.select(Some(start_line_num - 1 - idx));
-                        let selected_field_name = field_names[start_line_num - 1 - idx].clone();
-                        self.view_state.selected_object_detail_field_name = Some(selected_field_name);
-                        break;
-                    }
+                let (lines, field_paths) = self.produce_line_details_screen_content();
+                let idx = best(&mut lines.iter().map(String::as_str));
+                if let Some(idx) = idx {
+                    self.view_state.object_detail_list_state.select(Some(idx));
+                    self.view_state.selected_object_detail_field = field_paths.get(idx).cloned();
                 }
+                idx
             }
-            Screen::ValueDetails => {}
-        }
+            Screen::ValueDetails | Screen::RawLine => {
+                let idx = best(&mut self.produce_value_details_screen_content().lines());
+                if let Some(idx) = idx {
+                    self.view_state.value_screen_find_line = idx;
+                    self.view_state.value_screen_vertical_scroll_offset = self.value_screen_row_of_line(idx);
+                }
+                idx
+            }
+        };
+
+        find_task.found = Some(found.is_some());
+        self.update_match_position(&mut find_task);
         self.find_task = Some(find_task);
     }
 
-    fn find_matches_style() -> Style {
-        Style::new().on_yellow()
+    /// matches a line on the Main screen – its raw JSON or, in rendered mode, the text as shown
+    fn main_screen_line_matches(
+        &self,
+        find_task: &FindTask,
+        line: &RawJsonLine,
+    ) -> bool {
+        match find_task.rendered_mode {
+            true => find_task.matches(&self.render_main_screen_text(line)),
+            false => find_task.matches_line(line),
+        }
+    }
+
+    /// the text of a line as shown on the Main screen – its rows separated by line breaks
+    fn render_main_screen_text(
+        &self,
+        raw_line: &RawJsonLine,
+    ) -> String {
+        let rows: Vec<String> = self
+            .render_main_screen_rows(raw_line)
+            .iter()
+            .map(|row| row.spans.iter().map(|e| e.content.as_ref()).collect())
+            .collect();
+        rows.join("\n")
+    }
+
+    /// updates the match count (if outdated) and the position of the selected line among all matches
+    fn update_match_position(
+        &self,
+        find_task: &mut FindTask,
+    ) {
+        if find_task.match_indices.is_none() {
+            find_task.match_indices = Some(match self.active_screen {
+                Screen::Done | Screen::Loading => vec![],
+                Screen::Histogram => {
+                    let lines = self.produce_histogram_screen_content();
+                    lines
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, line)| find_task.matches(line))
+                        .map(|(idx, _)| idx)
+                        .collect()
+                }
+                Screen::Projection => {
+                    let lines = self.produce_projection_screen_content();
+                    lines
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, line)| find_task.matches(line))
+                        .map(|(pos, _)| pos)
+                        .collect()
+                }
+                Screen::Main => (0..self.num_visible_lines())
+                    .filter(|&pos| self.main_screen_line_matches(find_task, self.visible_line(pos).unwrap()))
+                    .collect(),
+                Screen::ObjectDetails => {
+                    let (lines, _) = self.produce_line_details_screen_content();
+                    lines
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, line)| find_task.matches(line))
+                        .map(|(idx, _)| idx)
+                        .collect()
+                }
+                Screen::ValueDetails | Screen::RawLine => {
+                    let text = self.produce_value_details_screen_content();
+                    text.lines()
+                        .enumerate()
+                        .filter(|(_, line)| find_task.matches(line))
+                        .map(|(idx, _)| idx)
+                        .collect()
+                }
+            });
+        }
+
+        let selected = match self.active_screen {
+            Screen::Done | Screen::Loading => None,
+            Screen::Main | Screen::Projection => self.view_state.main_window_list_state.selected(),
+            Screen::Histogram => self.view_state.histogram_list_state.selected(),
+            Screen::ObjectDetails => self.view_state.object_detail_list_state.selected(),
+            Screen::ValueDetails | Screen::RawLine => Some(self.view_state.value_screen_find_line),
+        };
+
+        find_task.match_ordinal = find_task
+            .match_indices
+            .as_ref()
+            .zip(selected)
+            .and_then(|(indices, e)| indices.binary_search(&e).ok())
+            .map(|e| e + 1);
+    }
+
+    /// colors a rendered JSON value by its type; search hit marks are kept
+    fn with_value_style<'b>(
+        &self,
+        span: Span<'b>,
+        value: &serde_json::Value,
+    ) -> Span<'b> {
+        let value_style = match value {
+            serde_json::Value::String(_) => Style::new().fg(self.props.theme.string_value_color),
+            serde_json::Value::Number(_) => Style::new().fg(self.props.theme.number_value_color),
+            serde_json::Value::Bool(_) | serde_json::Value::Null => Style::new().fg(self.props.theme.literal_value_color),
+            serde_json::Value::Array(_) | serde_json::Value::Object(_) => Style::new(),
+        };
+        let style = value_style.patch(span.style);
+        span.style(style)
     }
 }
 
 pub struct ModelIntoIter<'a> {
-    model: &'a Model<'a>,
+    model: &'a Model,
     index: usize,
 }
 
@@ -572,7 +3672,7 @@ impl ModelIntoIter<'_> {
     // light version of Self::next() that simply skips the item.
     // returns true if the item was skipped, false if there are no more items
     fn skip_item(&mut self) -> bool {
-        if self.index >= self.model.raw_json_lines.lines.len() {
+        if self.index >= self.model.num_visible_lines() {
             false
         } else {
             self.index += 1;
@@ -581,7 +3681,7 @@ impl ModelIntoIter<'_> {
     }
 }
 
-impl<'a> IntoIterator for &'a Model<'a> {
+impl<'a> IntoIterator for &'a Model {
     type Item = ListItem<'a>;
     type IntoIter = ModelIntoIter<'a>;
 
@@ -592,18 +3692,62 @@ impl<'a> Iterator for ModelIntoIter<'a> {
     type Item = ListItem<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let raw_line = self.model.raw_json_lines.lines.get(self.index)?;
-        let json = serde_json::from_str::<serde_json::Value>(&raw_line.content).expect("invalid json");
-        let line = match json {
-            serde_json::Value::Object(o) => self.model.render_json_line(&o),
-            e => Line::from(format!("{e}")),
+        let raw_line = self.model.visible_line(self.index)?;
+        let rows = self.model.render_main_screen_rows(raw_line);
+
+        let repeats = self.model.visible_line_repeats(self.index);
+        let badge: Vec<Span> = (repeats > 1)
+            .then(|| Span::raw(format!(" (×{repeats})")).italic())
+            .into_iter()
+            .chain(self.model.render_duplicate_keys_badge(raw_line))
+            .collect();
+        let badge_width: usize = badge.iter().map(|e| e.width()).sum();
+
+        // bookmark marker, line number and source badge in front
+        let prefix: Vec<Span> = self
+            .model
+            .render_bookmark_marker(self.model.visible_lines[self.index])
+            .into_iter()
+            .chain(self.model.render_line_number(raw_line))
+            .chain(self.model.render_source_badge(raw_line))
+            .flat_map(|e| [e, Span::raw(" ")])
+            .collect();
+        let prefix_width: usize = prefix.iter().map(|e| e.width()).sum();
+
+        // the badges go into the first row
+        let line_width = self.model.main_screen_line_width();
+        let mut rows: Vec<Line> = match self.model.view_state.main_screen_truncate_lines {
+            true => rows
+                .into_iter()
+                .enumerate()
+                .map(|(i, e)| match i {
+                    0 => truncated(e, line_width.saturating_sub(prefix_width + badge_width)),
+                    _ => truncated(e, line_width),
+                })
+                .collect(),
+            false => rows,
         };
+        // tinted by the log level – over the value colors
+        if let Some(style) = self.model.line_level_style(raw_line) {
+            for span in rows.iter_mut().flat_map(|e| e.spans.iter_mut()) {
+                span.style = span.style.patch(style);
+            }
+        }
+        if let Some(first_row) = rows.first_mut() {
+            first_row.extend(badge);
+            first_row.spans.splice(0..0, prefix);
+        }
 
+        let in_range = self.model.main_screen_range().is_some_and(|e| e.contains(&self.index));
         self.index += 1;
-        Some(ListItem::new(line))
+        match in_range {
+            true => Some(ListItem::new(rows).style(self.model.props.theme.range_style)),
+            false => Some(ListItem::new(rows)),
+        }
     }
 
-    fn size_hint(&self) -> (usize, Option<usize>) { (0, Some(self.model.raw_json_lines.lines.len() - self.index)) }
+    // counts lines, not rows – a pretty-printed line is a single (multi-row) item
+    fn size_hint(&self) -> (usize, Option<usize>) { (0, Some(self.model.num_visible_lines() - self.index)) }
 
     fn advance_by(
         &mut self,
@@ -618,3 +3762,319 @@ impl<'a> Iterator for ModelIntoIter<'a> {
         Ok(())
     }
 }
+
+/// pretty-prints the value like `serde_json::to_string_pretty` – with the objects and arrays nested deeper than `fold_level`
+/// collapsed into e.g. `{ … 3 fields }`
+fn to_string_pretty_folded(
+    value: &serde_json::Value,
+    fold_level: usize,
+) -> String {
+    fn write(
+        out: &mut String,
+        value: &serde_json::Value,
+        level: usize,
+        fold_level: usize,
+    ) {
+        let indent = |level: usize| "  ".repeat(level);
+        let count = |n: usize, noun: &str| format!("{n} {noun}{}", if n == 1 { "" } else { "s" });
+        match value {
+            serde_json::Value::Object(m) if m.is_empty() => out.push_str("{}"),
+            serde_json::Value::Array(a) if a.is_empty() => out.push_str("[]"),
+            serde_json::Value::Object(m) if level > fold_level => out.push_str(&format!("{{ … {} }}", count(m.len(), "field"))),
+            serde_json::Value::Array(a) if level > fold_level => out.push_str(&format!("[ … {} ]", count(a.len(), "item"))),
+            serde_json::Value::Object(m) => {
+                out.push_str("{\n");
+                for (i, (k, v)) in m.iter().enumerate() {
+                    out.push_str(&format!("{}{}: ", indent(level), serde_json::Value::from(k.as_str())));
+                    write(out, v, level + 1, fold_level);
+                    out.push_str(if i + 1 < m.len() { ",\n" } else { "\n" });
+                }
+                out.push_str(&format!("{}}}", indent(level - 1)));
+            }
+            serde_json::Value::Array(a) => {
+                out.push_str("[\n");
+                for (i, v) in a.iter().enumerate() {
+                    out.push_str(&indent(level));
+                    write(out, v, level + 1, fold_level);
+                    out.push_str(if i + 1 < a.len() { ",\n" } else { "\n" });
+                }
+                out.push_str(&format!("{}]", indent(level - 1)));
+            }
+            e => out.push_str(&e.to_string()),
+        }
+    }
+
+    let mut out = String::new();
+    write(&mut out, value, 1, fold_level);
+    out
+}
+
+/// nesting depth of the objects and arrays in the value – 0 for a scalar value
+fn json_depth(value: &serde_json::Value) -> usize {
+    match value {
+        serde_json::Value::Object(m) => 1 + m.values().map(json_depth).max().unwrap_or(0),
+        serde_json::Value::Array(a) => 1 + a.iter().map(json_depth).max().unwrap_or(0),
+        _ => 0,
+    }
+}
+
+/// the JSON document (object or array) encoded in a string, e.g. `"{\"a\":1}"`; None for any other string
+fn embedded_json(s: &str) -> Option<serde_json::Value> {
+    serde_json::from_str::<serde_json::Value>(s)
+        .ok()
+        .filter(|e| e.is_object() || e.is_array())
+}
+
+/// e.g. `987`, `12.3k` or `4.5M`
+fn format_count(n: usize) -> String {
+    match n {
+        ..1_000 => n.to_string(),
+        1_000..1_000_000 => format!("{:.1}k", n as f64 / 1_000.0),
+        _ => format!("{:.1}M", n as f64 / 1_000_000.0),
+    }
+}
+
+/// wraps the line into rows of the given width; the continuation rows are indented (by half the width at most)
+fn wrapped(
+    line: Line,
+    width: usize,
+    indent: usize,
+) -> Vec<Line> {
+    let indent = indent.min(width / 2);
+    let mut rows = vec![];
+    let mut row = Line::default().style(line.style);
+    let mut row_width = 0;
+    for span in line.spans {
+        let mut content = String::new();
+        for c in span.content.chars() {
+            let char_width = c.width().unwrap_or(0);
+            // at least one character per row
+            if row_width + char_width > width && row_width > indent {
+                if !content.is_empty() {
+                    row.push_span(Span::styled(mem::take(&mut content), span.style));
+                }
+                rows.push(mem::replace(&mut row, Line::from(" ".repeat(indent)).style(line.style)));
+                row_width = indent;
+            }
+            content.push(c);
+            row_width += char_width;
+        }
+        if !content.is_empty() {
+            row.push_span(Span::styled(content, span.style));
+        }
+    }
+    rows.push(row);
+    rows
+}
+
+/// cuts the line to `max_width` columns, if it is wider; a cut line ends with `…`
+fn truncated(
+    line: Line,
+    max_width: usize,
+) -> Line {
+    if line.width() <= max_width {
+        return line;
+    }
+
+    let mut remaining_width = max_width.saturating_sub(1);
+    let mut spans = vec![];
+    for span in line.spans {
+        let content: String = span
+            .content
+            .chars()
+            .take_while(|c| match remaining_width.checked_sub(c.width().unwrap_or(0)) {
+                Some(e) => {
+                    remaining_width = e;
+                    true
+                }
+                None => {
+                    remaining_width = 0;
+                    false
+                }
+            })
+            .collect();
+        let cut = content.len() < span.content.len();
+        spans.push(Span::styled(content, span.style));
+        if cut {
+            break;
+        }
+    }
+    spans.push(Span::raw("…"));
+
+    Line::from(spans).style(line.style)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model_with_lines(
+        lines: &[&str],
+        terminal_size: Size,
+    ) -> Model {
+        let mut raw_lines = RawJsonLines::default();
+        for (i, line) in lines.iter().enumerate() {
+            raw_lines.push(SourceName::JsonFile("test.jsonl".to_string()), i + 1, line.to_string());
+        }
+        Model::new(Props::default(), terminal_size, raw_lines, ViewOptions::default())
+    }
+
+    fn open_file_by_messages(
+        model: Model,
+        append: bool,
+    ) -> Model {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/events.jsonl");
+        path.chars()
+            .fold(model.step(Message::OpenFileTask { append }), |model, c| {
+                model.step(Message::CharacterInput(c))
+            })
+            .step(Message::Enter)
+    }
+
+    #[test]
+    fn dialog_points_out_keys_without_function() {
+        let model = model_with_lines(&["{}"], Size::new(80, 20))
+            .step(Message::OpenFilterTask)
+            .step(Message::CycleSort);
+        assert!(model.has_filter_task());
+        assert_eq!(model.last_action_result, KEY_WITHOUT_FUNCTION);
+    }
+
+    #[test]
+    fn reload_loads_the_opened_files_again() {
+        // the input files, replaced by an opened file, are left out
+        let mut model = open_file_by_messages(model_with_lines(&["{}"], Size::new(80, 20)), false).step(Message::Reload);
+        assert!(!model.take_reload_request());
+        assert_eq!(model.num_visible_lines(), 4);
+        assert_eq!(model.last_action_result, "Ok: 4 lines reloaded");
+
+        // an appended file follows the reloaded input files
+        let mut model = open_file_by_messages(model_with_lines(&["{}"], Size::new(80, 20)), true).step(Message::Reload);
+        assert!(model.take_reload_request());
+        let mut input_lines = RawJsonLines::default();
+        input_lines.push(SourceName::JsonFile("test.jsonl".to_string()), 1, "{}".to_string());
+        model.reloaded(Ok(input_lines), &LoadProgress::new(1));
+        assert_eq!(model.num_visible_lines(), 5);
+    }
+
+    #[test]
+    fn backward_search_order_reaches_first_line() {
+        let order: Vec<usize> = SearchDirection::Backward.search_order(Some(1), 3, true).collect();
+        assert_eq!(order, [0]);
+        let order: Vec<usize> = SearchDirection::Backward.search_order(Some(0), 3, false).collect();
+        assert_eq!(order, [0]);
+    }
+
+    #[test]
+    fn find_previous_reaches_match_on_first_line() {
+        let model = model_with_lines(&[r#"{"msg":"needle"}"#, r#"{"msg":"hay"}"#, r#"{"msg":"hay"}"#], Size::new(80, 24));
+        let mut model = model.step(Message::Last).step(Message::OpenFindTask);
+        for c in "needle".chars() {
+            model = model.step(Message::CharacterInput(c));
+        }
+        assert_eq!(model.view_state.main_window_list_state.selected(), Some(2));
+
+        let model = model.step(Message::ScrollUp);
+        assert_eq!(model.view_state.main_window_list_state.selected(), Some(0));
+    }
+
+    #[test]
+    fn page_scroll_overlaps_by_one_line() {
+        for height in [0, 1, 2, 3, 4, 5, 10, 24, 100] {
+            let model = model_with_lines(&[], Size::new(80, height));
+            let page_len = model.page_len();
+            let scroll_len = model.page_scroll_len(Message::PageDown);
+            assert_eq!(scroll_len, model.page_scroll_len(Message::PageUp));
+            match page_len {
+                // no room for an overlap – but a page scroll still moves on
+                0 | 1 => assert_eq!(scroll_len, 1, "height {height}"),
+                _ => assert_eq!(scroll_len, page_len - 1, "height {height}"),
+            }
+            assert!(model.page_scroll_len(Message::HalfPageDown) >= 1, "height {height}");
+        }
+    }
+
+    #[test]
+    fn next_tab_switches_tabs_but_case_insensitive_toggle_does_not() {
+        let mut raw_lines = RawJsonLines::default();
+        raw_lines.push(SourceName::JsonFile("a.jsonl".to_string()), 1, r#"{"a":1}"#.to_string());
+        raw_lines.push(SourceName::JsonFile("b.jsonl".to_string()), 1, r#"{"b":1}"#.to_string());
+        let options = ViewOptions {
+            tabs: true,
+            ..Default::default()
+        };
+        let model = Model::new(Props::default(), Size::new(80, 24), raw_lines, options);
+        assert_eq!(model.selected_raw_line_idx(), Some(0));
+
+        let model = model.step(Message::ToggleFindCaseInsensitive);
+        assert_eq!(model.selected_raw_line_idx(), Some(0));
+
+        let model = model.step(Message::NextTab);
+        assert_eq!(model.selected_raw_line_idx(), Some(1));
+    }
+
+    #[test]
+    fn find_on_value_screen_scrolls_to_wrapped_row() {
+        let long = "x".repeat(200);
+        let line = format!(r#"{{"msg":"{long}\nneedle"}}"#);
+        // the field is selected by finding it on the ObjectDetails screen – without rendering the screen
+        let mut model = model_with_lines(&[&line], Size::new(80, 24))
+            .step(Message::Enter)
+            .step(Message::OpenFindTask);
+        for c in "msg".chars() {
+            model = model.step(Message::CharacterInput(c));
+        }
+        let model = model.step(Message::Exit).step(Message::Enter);
+        assert!(model.active_screen == Screen::ValueDetails);
+
+        let mut model = model.step(Message::OpenFindTask);
+        for c in "needle".chars() {
+            model = model.step(Message::CharacterInput(c));
+        }
+        // the 200 columns of the first line take 3 rows of 78 columns
+        assert_eq!(model.view_state.value_screen_find_line, 1);
+        assert_eq!(model.view_state.value_screen_vertical_scroll_offset, 3);
+    }
+
+    #[test]
+    fn object_details_without_selected_line_show_a_note() {
+        let mut model = model_with_lines(&[r#"{"a":1}"#], Size::new(80, 24)).step(Message::Enter);
+        assert!(model.active_screen == Screen::ObjectDetails);
+
+        model.view_state.main_window_list_state.select(None);
+        let (lines, field_paths) = model.produce_line_details_screen_content();
+        assert_eq!(lines, [LINE_GONE_NOTE]);
+        assert!(field_paths.is_empty());
+        assert_eq!(model.render_object_detail_path(), "");
+    }
+
+    #[test]
+    fn case_insensitive_terms_match_lines() {
+        let mut raw_lines = RawJsonLines::default();
+        raw_lines.push(
+            SourceName::JsonFile("test.jsonl".to_string()),
+            1,
+            r#"{"Level":"ERROR","msg":"Disk Full"}"#.to_string(),
+        );
+        let line = &raw_lines.lines[0];
+
+        let mut find_task = FindTask::default();
+        for c in "Level:error disk|cpu".chars() {
+            find_task.add_search_char(c);
+        }
+        assert!(!find_task.matches_line(line));
+        find_task.toggle_case_insensitive();
+        assert!(find_task.matches_line(line));
+        assert!(find_task.matches("DISK level:ERROR"));
+        assert_eq!(find_task.find_in("a DISK"), Some(2..6));
+
+        // the field name stays case-sensitive
+        for _ in 0.."Level:error disk|cpu".len() {
+            find_task.remove_search_char();
+        }
+        for c in "level:error".chars() {
+            find_task.add_search_char(c);
+        }
+        assert!(!find_task.matches_line(line));
+    }
+}
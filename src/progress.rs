@@ -0,0 +1,61 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// Progress of loading the input files – updated by the loading threads and shown on the Loading screen.
+pub struct LoadProgress {
+    num_files: usize,
+    num_loaded_files: AtomicUsize,
+    num_lines: AtomicUsize,
+    num_bytes: AtomicUsize,
+    /// notes about the input (e.g. truncated files), which can't be printed while the terminal UI is active
+    notes: Mutex<Vec<String>>,
+    started: Instant,
+}
+
+impl LoadProgress {
+    pub fn new(num_files: usize) -> Self {
+        LoadProgress {
+            num_files,
+            num_loaded_files: AtomicUsize::new(0),
+            num_lines: AtomicUsize::new(0),
+            num_bytes: AtomicUsize::new(0),
+            notes: Mutex::new(vec![]),
+            started: Instant::now(),
+        }
+    }
+
+    /// counts a line read with the given length (without line break)
+    pub fn line_read(
+        &self,
+        len: usize,
+    ) {
+        self.num_lines.fetch_add(1, Ordering::Relaxed);
+        self.num_bytes.fetch_add(len + 1, Ordering::Relaxed);
+    }
+
+    pub fn file_loaded(&self) { self.num_loaded_files.fetch_add(1, Ordering::Relaxed); }
+
+    pub fn note(
+        &self,
+        note: String,
+    ) {
+        self.notes.lock().expect("notes should not be poisoned").push(note);
+    }
+
+    pub fn take_notes(&self) -> Vec<String> { std::mem::take(&mut *self.notes.lock().expect("notes should not be poisoned")) }
+
+    /// e.g. `⠹ loading files: 2/5 – 1234567 lines (345.6 MB) read` – the spinner turns while the screen is redrawn
+    pub fn render(&self) -> String {
+        let spinner = SPINNER[(self.started.elapsed().as_millis() / 100) as usize % SPINNER.len()];
+        format!(
+            "{spinner} loading files: {}/{} – {} lines ({:.1} MB) read",
+            self.num_loaded_files.load(Ordering::Relaxed),
+            self.num_files,
+            self.num_lines.load(Ordering::Relaxed),
+            self.num_bytes.load(Ordering::Relaxed) as f64 / 1_000_000.0
+        )
+    }
+}
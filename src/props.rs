@@ -1,32 +1,188 @@
+use crate::keys::KeyBindings;
+use crate::load::is_url;
+use crate::theme::Theme;
 use anyhow::Context;
 use serde::{Deserialize, Serialize};
-use std::fs;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
+use std::sync::mpsc;
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, SystemTime};
+use std::{fs, path, thread};
 
+const MAX_RECENT_SESSIONS: usize = 10;
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// a named field order, e.g. for a known log format
 #[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct Preset {
+    pub fields_order: Vec<String>,
+    pub fields_suppressed: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(default)]
 pub struct Props {
     pub fields_order: Vec<String>,
     pub fields_suppressed: Vec<String>,
+    /// hide fields with a null or empty value
+    pub suppress_empty_fields: bool,
+    /// input files of the recent sessions; most recent first
+    pub recent_sessions: Vec<Vec<PathBuf>>,
+    /// interval in milliseconds to check for input, while the screen is redrawn periodically (e.g. for followed files);
+    /// None for the default
+    pub poll_ms: Option<u64>,
+    /// named field orders to switch between, e.g. `[presets.webserver]`
+    pub presets: BTreeMap<String, Preset>,
+    pub theme: Theme,
+    pub keys: KeyBindings,
+    /// the preset in use instead of the field order above; None to use that one
+    #[serde(skip)]
+    pub preset: Option<String>,
+    /// config file given on the commandline; None for the default location
+    #[serde(skip)]
+    config_file: Option<PathBuf>,
 }
 
 impl Props {
-    pub fn config_file_path() -> Option<PathBuf> {
-        dirs::config_dir().map(|e| e.join("json-lines-viewer.toml"))
+    pub fn config_file_path() -> Option<PathBuf> { dirs::config_dir().map(|e| e.join("json-lines-viewer.toml")) }
+
+    /// the state file next to the config file (e.g. for the last selected lines)
+    pub fn state_file_path(&self) -> Option<PathBuf> {
+        self.config_file
+            .clone()
+            .or_else(Self::config_file_path)
+            .map(|e| e.with_extension("state.toml"))
     }
 
-    pub fn init() -> anyhow::Result<Props> {
-        let Some(f) = &Self::config_file_path().filter(|f| f.exists()) else {
-            return Ok(Props::default());
+    /// loads the props from the given config file or the one at the default location.
+    /// Settings are saved to the same file later.
+    pub fn init(config_file: Option<PathBuf>) -> anyhow::Result<Props> {
+        let Some(f) = &config_file.clone().or_else(Self::config_file_path).filter(|f| f.exists()) else {
+            return Ok(Props {
+                config_file,
+                ..Default::default()
+            });
         };
 
         let props = fs::read_to_string(f).with_context(|| format!("failed to read config file {f:?}"))?;
-        let props = toml::from_str::<Props>(&props).context("failed to parse config file as toml")?;
+        let mut props = toml::from_str::<Props>(&props).context("failed to parse config file as toml")?;
+        props.config_file = config_file;
 
         Ok(props)
     }
 
+    pub fn poll_interval(&self) -> Duration { self.poll_ms.map_or(DEFAULT_POLL_INTERVAL, Duration::from_millis) }
+
+    /// switches to the field order of the named preset; false if there is no such preset.
+    /// The field order above stays as it is – it's used again without a preset.
+    pub fn apply_preset(
+        &mut self,
+        name: &str,
+    ) -> bool {
+        if !self.presets.contains_key(name) {
+            return false;
+        }
+        self.preset = Some(name.to_string());
+        true
+    }
+
+    /// the field order in use – the one of the active preset, otherwise the one above
+    pub fn active_fields_order(&self) -> &[String] {
+        match self.active_preset() {
+            Some(preset) => &preset.fields_order,
+            None => &self.fields_order,
+        }
+    }
+
+    /// the suppressed fields in use – those of the active preset, otherwise the ones above
+    pub fn active_fields_suppressed(&self) -> &[String] {
+        match self.active_preset() {
+            Some(preset) => &preset.fields_suppressed,
+            None => &self.fields_suppressed,
+        }
+    }
+
+    /// the field order in use for changing it – changes to a preset are kept in the preset
+    pub fn active_fields_order_mut(&mut self) -> &mut Vec<String> {
+        match self.preset.as_ref().and_then(|e| self.presets.get_mut(e)) {
+            Some(preset) => &mut preset.fields_order,
+            None => &mut self.fields_order,
+        }
+    }
+
+    /// the suppressed fields in use for changing them – changes to a preset are kept in the preset
+    pub fn active_fields_suppressed_mut(&mut self) -> &mut Vec<String> {
+        match self.preset.as_ref().and_then(|e| self.presets.get_mut(e)) {
+            Some(preset) => &mut preset.fields_suppressed,
+            None => &mut self.fields_suppressed,
+        }
+    }
+
+    fn active_preset(&self) -> Option<&Preset> { self.preset.as_ref().and_then(|e| self.presets.get(e)) }
+
+    /// puts the given files on top of the recent sessions
+    pub fn add_recent_session(
+        &mut self,
+        files: &[PathBuf],
+    ) {
+        let files: Vec<PathBuf> = files
+            .iter()
+            .map(|e| match is_url(e) {
+                true => e.clone(),
+                false => path::absolute(e).unwrap_or_else(|_| e.clone()),
+            })
+            .collect();
+        self.recent_sessions.retain(|e| e != &files);
+        self.recent_sessions.insert(0, files);
+        self.recent_sessions.truncate(MAX_RECENT_SESSIONS);
+    }
+
+    /// Watches the config file for changes and delivers the reloaded props – or the error, if the file can't be loaded.
+    /// The file is polled by a background thread; `overrides` is applied to each reloaded props (e.g. commandline options).
+    pub fn watch(
+        &self,
+        overrides: impl Fn(&mut Props) + Send + 'static,
+    ) -> Receiver<anyhow::Result<Props>> {
+        let (sender, receiver) = mpsc::channel();
+        let config_file = self.config_file.clone();
+
+        thread::spawn(move || {
+            let Some(f) = config_file.clone().or_else(Self::config_file_path) else {
+                return;
+            };
+            let modified = || -> Option<SystemTime> { fs::metadata(&f).and_then(|e| e.modified()).ok() };
+            let mut last_modified = modified();
+
+            loop {
+                thread::sleep(WATCH_INTERVAL);
+                let m = modified();
+                if m == last_modified || m.is_none() {
+                    continue;
+                }
+                last_modified = m;
+
+                let props = Props::init(config_file.clone()).map(|mut props| {
+                    overrides(&mut props);
+                    props
+                });
+                if sender.send(props).is_err() {
+                    return; // receiver is gone
+                }
+            }
+        });
+
+        receiver
+    }
+
     pub fn save(&self) -> anyhow::Result<()> {
-        let f = Self::config_file_path().context("Config dir not found")?;
+        let f = self
+            .config_file
+            .clone()
+            .or_else(Self::config_file_path)
+            .context("Config dir not found")?;
         let toml = toml::to_string_pretty(self)?;
 
         std::fs::write(&f, toml)?;
@@ -34,3 +190,34 @@ impl Props {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preset_keeps_base_field_order() {
+        let mut props = Props {
+            fields_order: vec!["timestamp".to_string(), "message".to_string()],
+            ..Default::default()
+        };
+        props.presets.insert(
+            "web".to_string(),
+            Preset {
+                fields_order: vec!["status".to_string()],
+                fields_suppressed: vec!["headers".to_string()],
+            },
+        );
+
+        assert!(props.apply_preset("web"));
+        assert_eq!(props.active_fields_order(), ["status"]);
+        assert_eq!(props.active_fields_suppressed(), ["headers"]);
+
+        let saved = toml::from_str::<Props>(&toml::to_string_pretty(&props).unwrap()).unwrap();
+        assert_eq!(saved.fields_order, ["timestamp", "message"]);
+        assert!(saved.fields_suppressed.is_empty());
+
+        props.preset = None;
+        assert_eq!(props.active_fields_order(), ["timestamp", "message"]);
+    }
+}
@@ -1,16 +1,25 @@
+use crate::duplicate_keys::{DuplicateKey, find_duplicate_keys};
+use chrono::{DateTime, NaiveDateTime, Utc};
 use rustc_hash::FxHashMap;
+use std::cell::OnceCell;
 use std::fmt::{Display, Formatter};
 
+pub const ATTRIBUTE_SEPARATOR: &str = " : ";
+
+/// epoch numbers at or above this are taken as milliseconds, below as seconds (~ year 5138 in seconds)
+const EPOCH_MILLIS_THRESHOLD: f64 = 1e11;
+
 #[derive(Default)]
 pub struct RawJsonLines {
-    sources: FxHashMap<usize, SourceName>,
+    /// source names by source id – in the order of their first line
+    sources: Vec<SourceName>,
+    /// source ids by source name
+    source_ids: FxHashMap<SourceName, usize>,
     pub lines: Vec<RawJsonLine>,
 }
 
 impl RawJsonLines {
-    pub fn is_empty(&self) -> bool {
-        self.lines.is_empty()
-    }
+    pub fn is_empty(&self) -> bool { self.lines.is_empty() }
 
     pub fn push(
         &mut self,
@@ -23,35 +32,91 @@ impl RawJsonLines {
             source_id,
             line_nr,
             content,
+            json: OnceCell::new(),
+            duplicate_keys: OnceCell::new(),
         })
     }
 
+    /// appends the lines of another instance; its sources are numbered on as if their lines were pushed here
+    pub fn append(
+        &mut self,
+        other: RawJsonLines,
+    ) {
+        let source_ids: Vec<usize> = other.sources.into_iter().map(|e| self.source_id(e)).collect();
+
+        self.lines.extend(other.lines.into_iter().map(|mut e| {
+            e.source_id = source_ids[e.source_id];
+            e
+        }));
+    }
+
+    pub fn num_sources(&self) -> usize { self.sources.len() }
+
     pub fn source_name(
         &self,
         source_id: usize,
     ) -> Option<&SourceName> {
-        self.sources.get(&source_id)
+        self.sources.get(source_id)
+    }
+
+    /// the largest line number of the source's lines; 0 without lines
+    pub fn last_line_nr_of(
+        &self,
+        source_name: &SourceName,
+    ) -> usize {
+        let Some(&source_id) = self.source_ids.get(source_name) else {
+            return 0;
+        };
+        self.lines
+            .iter()
+            .filter(|e| e.source_id == source_id)
+            .map(|e| e.line_nr)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// stable sorts the lines by the timestamp in the given top level field (ISO-8601 string or epoch number),
+    /// so that lines from multiple sources are interleaved chronologically; lines without a timestamp go to the end
+    pub fn merge_by_timestamp(
+        &mut self,
+        field: &str,
+    ) {
+        self.lines.sort_by_cached_key(|e| {
+            let timestamp = e.timestamp_at(field);
+            (timestamp.is_none(), timestamp)
+        });
     }
 
     fn source_id(
         &mut self,
         source_name: SourceName,
     ) -> usize {
-        if let Some((k, _)) = self.sources.iter().find(|&(_, v)| v == &source_name) {
-            *k
-        } else {
-            let id = self.sources.len();
-            _ = self.sources.insert(id, source_name);
-            id
+        if let Some(&id) = self.source_ids.get(&source_name) {
+            return id;
         }
+        let id = self.sources.len();
+        self.source_ids.insert(source_name.clone(), id);
+        self.sources.push(source_name);
+        id
     }
 }
 
-#[derive(PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub enum SourceName {
     JsonFile(String),
     JsonInZip { zip_file: String, json_file: String },
 }
+
+impl SourceName {
+    /// the name of the JSON file without directories (e.g. of a URL path)
+    pub fn short_name(&self) -> &str {
+        match self {
+            SourceName::JsonFile(e) => e.rsplit('/').next().unwrap_or(e),
+            SourceName::JsonInZip { json_file, .. } => json_file.rsplit('/').next().unwrap_or(json_file),
+        }
+    }
+}
+
 impl Display for SourceName {
     fn fmt(
         &self,
@@ -63,34 +128,199 @@ impl Display for SourceName {
         }
     }
 }
+/// a field of a JSON object or an item of a JSON array
+pub struct Field<'a> {
+    /// object key or array index – or the path of them for flattened fields
+    pub path: Vec<String>,
+    /// displayed name – the object key or `[index]`; dotted keys like `a.b[0]` for flattened fields
+    pub label: String,
+    pub value: &'a serde_json::Value,
+}
+
 pub struct RawJsonLine {
     pub source_id: usize,
     pub line_nr: usize,
     pub content: String,
+    json: OnceCell<Result<serde_json::Value, String>>,
+    duplicate_keys: OnceCell<Vec<DuplicateKey>>,
+}
+
+/// parses a timestamp – either an ISO-8601 string (UTC, if no offset is given)
+/// or a number of seconds (or milliseconds for large numbers) since the epoch
+pub fn parse_timestamp(value: &serde_json::Value) -> Option<DateTime<Utc>> {
+    match value {
+        serde_json::Value::String(s) => DateTime::parse_from_rfc3339(s)
+            .map(|e| e.to_utc())
+            .or_else(|_| s.parse::<NaiveDateTime>().map(|e| e.and_utc()))
+            .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f").map(|e| e.and_utc()))
+            .ok(),
+        serde_json::Value::Number(n) => {
+            let n = n.as_f64()?;
+            let millis = if n.abs() < EPOCH_MILLIS_THRESHOLD { n * 1000.0 } else { n };
+            DateTime::from_timestamp_millis(millis as i64)
+        }
+        _ => None,
+    }
+}
+
+/// true for null, an empty string, an empty array or an empty object
+pub fn is_empty_value(value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Null => true,
+        serde_json::Value::String(s) => s.is_empty(),
+        serde_json::Value::Array(a) => a.is_empty(),
+        serde_json::Value::Object(o) => o.is_empty(),
+        serde_json::Value::Bool(_) | serde_json::Value::Number(_) => false,
+    }
 }
 
 impl RawJsonLine {
-    /// returns JSON object lines and keys in rendered order
-    pub fn produce_rendered_fields_as_list(&self, key_order: &[String]) -> (Vec<String>, Vec<String>) {
-        let value = serde_json::from_str(&self.content).expect("not a json value");
+    /// returns the parsed line content or the parse error message for malformed JSON.
+    /// The line is parsed only once; the result is kept for subsequent calls.
+    pub fn json(&self) -> Result<&serde_json::Value, &str> {
+        self.json
+            .get_or_init(|| serde_json::from_str(&self.content).map_err(|e| e.to_string()))
+            .as_ref()
+            .map_err(|e| e.as_str())
+    }
 
-        let serde_json::Value::Object(o) = value else {
-            panic!("line should be in json object format")
-        };
+    /// returns the keys occurring more than once in an object of the line (see [find_duplicate_keys]).
+    /// The line is scanned only once – on the first call.
+    pub fn duplicate_keys(&self) -> &[DuplicateKey] { self.duplicate_keys.get_or_init(|| find_duplicate_keys(&self.content)) }
+
+    /// returns the nested value addressed by a path of object keys / array indices
+    pub fn value_at(
+        &self,
+        path: &[String],
+    ) -> Option<&serde_json::Value> {
+        let mut value = self.json().ok()?;
+        for segment in path {
+            value = match value {
+                serde_json::Value::Object(o) => o.get(segment)?,
+                serde_json::Value::Array(a) => a.get(segment.parse::<usize>().ok()?)?,
+                _ => return None,
+            };
+        }
+        Some(value)
+    }
+
+    /// returns the timestamp in the given top level field (see [parse_timestamp])
+    pub fn timestamp_at(
+        &self,
+        field: &str,
+    ) -> Option<DateTime<Utc>> {
+        parse_timestamp(self.json().ok()?.as_object()?.get(field)?)
+    }
+
+    /// returns the fields of the object (or the items of the array) at `path` in rendered order;
+    /// None if there is no object or array.
+    /// The key order is applied to the top level object only; array items are keyed by their index.
+    /// With `suppress_empty` object fields with an empty value (see [is_empty_value]) are left out.
+    /// With `flatten` nested objects and arrays are replaced by their (recursively flattened) fields.
+    pub fn fields_at(
+        &self,
+        key_order: &[String],
+        path: &[String],
+        suppress_empty: bool,
+        flatten: bool,
+    ) -> Option<Vec<Field<'_>>> {
+        let fields: Vec<Field> = match self.value_at(path)? {
+            serde_json::Value::Object(o) => {
+                let key_order = if path.is_empty() { key_order } else { &[] };
 
-        let mut keys_in_rendered_order: Vec<_> = key_order.iter().filter(|&e| o.contains_key(e)).cloned().collect();
-        keys_in_rendered_order.extend(o.keys().filter(|&e| !key_order.contains(e)).cloned());
+                let mut keys_in_rendered_order: Vec<_> = key_order.iter().filter(|&e| o.contains_key(e)).collect();
+                keys_in_rendered_order.extend(o.keys().filter(|&e| !key_order.contains(e)));
 
-        let mut list_items = vec![];
+                keys_in_rendered_order
+                    .into_iter()
+                    .map(|k| Field {
+                        path: vec![k.clone()],
+                        label: k.clone(),
+                        value: o.get(k).unwrap(),
+                    })
+                    .filter(|e| !(suppress_empty && is_empty_value(e.value)))
+                    .collect()
+            }
+            serde_json::Value::Array(a) => a
+                .iter()
+                .enumerate()
+                .map(|(i, value)| Field {
+                    path: vec![i.to_string()],
+                    label: format!("[{i}]"),
+                    value,
+                })
+                .collect(),
+            _ => return None,
+        };
 
-        for k in &keys_in_rendered_order {
-            list_items.push(Self::render_attribute(k, o.get(k).unwrap()));
+        if !flatten {
+            return Some(fields);
+        }
+        let mut flattened = vec![];
+        for field in fields {
+            Self::flatten_field(field, suppress_empty, &mut flattened);
+        }
+        Some(flattened)
+    }
+
+    /// adds the field – or the fields of a non-empty object or array value recursively – with dotted labels
+    fn flatten_field<'a>(
+        field: Field<'a>,
+        suppress_empty: bool,
+        flattened: &mut Vec<Field<'a>>,
+    ) {
+        let nested_field = |key: String, label: String, value| {
+            let mut path = field.path.clone();
+            path.push(key);
+            Field { path, label, value }
+        };
+        match field.value {
+            serde_json::Value::Object(o) if !o.is_empty() => {
+                for (k, v) in o.iter().filter(|(_, v)| !(suppress_empty && is_empty_value(v))) {
+                    Self::flatten_field(
+                        nested_field(k.clone(), format!("{}.{k}", field.label), v),
+                        suppress_empty,
+                        flattened,
+                    );
+                }
+            }
+            serde_json::Value::Array(a) if !a.is_empty() => {
+                for (i, v) in a.iter().enumerate() {
+                    Self::flatten_field(
+                        nested_field(i.to_string(), format!("{}[{i}]", field.label), v),
+                        suppress_empty,
+                        flattened,
+                    );
+                }
+            }
+            _ => flattened.push(field),
         }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        (list_items, keys_in_rendered_order)
+    #[test]
+    fn line_is_parsed_only_once() {
+        let mut raw_lines = RawJsonLines::default();
+        raw_lines.push(SourceName::JsonFile("test.jsonl".to_string()), 1, r#"{"a":1}"#.to_string());
+        let line = &raw_lines.lines[0];
+
+        let first = line.json().unwrap();
+        for _ in 0..3 {
+            assert!(std::ptr::eq(first, line.json().unwrap()));
+        }
     }
 
-    fn render_attribute(key: &str, value: &serde_json::Value) -> String {
-        format!("{key} : {value}")
+    #[test]
+    fn parse_error_is_parsed_only_once() {
+        let mut raw_lines = RawJsonLines::default();
+        raw_lines.push(SourceName::JsonFile("test.jsonl".to_string()), 1, "{broken".to_string());
+        let line = &raw_lines.lines[0];
+
+        let first = line.json().unwrap_err();
+        assert!(std::ptr::eq(first, line.json().unwrap_err()));
     }
 }
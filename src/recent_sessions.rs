@@ -0,0 +1,67 @@
+use crate::props::Props;
+use crate::terminal;
+use crate::theme::Theme;
+use anyhow::{Context, anyhow};
+use crossterm::event;
+use crossterm::event::{Event, KeyCode, KeyEventKind};
+use ratatui::Terminal;
+use ratatui::prelude::{Backend, Line};
+use ratatui::widgets::{Block, List, ListState};
+use std::path::PathBuf;
+
+/// Shows a list of the recent sessions to pick one from.
+/// Returns the files of the selected session or None, if the selection was cancelled.
+pub fn select_recent_session(props: &Props) -> anyhow::Result<Option<Vec<PathBuf>>> {
+    let sessions = &props.recent_sessions;
+    let mut terminal = terminal::init_terminal().context("failed to initialize terminal")?;
+    let selection = run_selection(&mut terminal, sessions, &props.theme);
+    terminal::restore_terminal().context("failed to restore terminal state")?;
+
+    Ok(selection?.map(|i| sessions[i].clone()))
+}
+
+fn run_selection(
+    terminal: &mut Terminal<impl Backend>,
+    sessions: &[Vec<PathBuf>],
+    theme: &Theme,
+) -> anyhow::Result<Option<usize>> {
+    let items: Vec<Line> = sessions
+        .iter()
+        .map(|files| {
+            let files: Vec<_> = files.iter().map(|e| e.to_string_lossy()).collect();
+            Line::from(files.join(", "))
+        })
+        .collect();
+    let mut list_state = ListState::default().with_selected(Some(0));
+
+    loop {
+        terminal
+            .draw(|f| {
+                let list = List::new(items.clone())
+                    .block(
+                        Block::bordered()
+                            .title(" Recent sessions ")
+                            .title_bottom(Line::from(" Enter: open | Esc: quit ").left_aligned()),
+                    )
+                    .highlight_style(theme.highlight_style)
+                    .highlight_symbol(theme.highlight_symbol.as_str());
+                f.render_stateful_widget(list, f.area(), &mut list_state);
+            })
+            .map_err(|e| anyhow!("{e}"))
+            .context("failed to draw to terminal")?;
+
+        let Event::Key(key) = event::read().context("failed to read event")? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => list_state.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => list_state.select_next(),
+            KeyCode::Enter => return Ok(list_state.selected().map(|i| i.min(sessions.len() - 1))),
+            KeyCode::Esc | KeyCode::Char('q') => return Ok(None),
+            _ => (),
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use crate::raw_json_lines::RawJsonLine;
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+
+/// Sort order of the lines by a field.
+/// Values are compared numerically if both are numbers (or strings containing a number), lexically otherwise.
+/// Lines without the field are sorted last – in both directions.
+#[derive(Clone, Debug)]
+pub struct LineSort {
+    pub field: String,
+    pub descending: bool,
+}
+
+impl LineSort {
+    pub fn compare(
+        &self,
+        a: &RawJsonLine,
+        b: &RawJsonLine,
+    ) -> Ordering {
+        match (self.field_value(a), self.field_value(b)) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(_), None) => Ordering::Less,
+            (Some(a), Some(b)) => {
+                let ordering = match (as_number(a), as_number(b)) {
+                    (Some(a), Some(b)) => a.total_cmp(&b),
+                    _ => as_text(a).cmp(&as_text(b)),
+                };
+                if self.descending { ordering.reverse() } else { ordering }
+            }
+        }
+    }
+
+    fn field_value<'a>(
+        &self,
+        line: &'a RawJsonLine,
+    ) -> Option<&'a serde_json::Value> {
+        line.json().ok()?.as_object()?.get(&self.field)
+    }
+}
+
+fn as_number(value: &serde_json::Value) -> Option<f64> {
+    match value {
+        serde_json::Value::Number(n) => n.as_f64(),
+        serde_json::Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn as_text(value: &serde_json::Value) -> Cow<'_, str> {
+    match value {
+        serde_json::Value::String(s) => Cow::Borrowed(s),
+        e => Cow::Owned(e.to_string()),
+    }
+}
+
+impl Display for LineSort {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(f, "{} {}", self.field, if self.descending { "↓" } else { "↑" })
+    }
+}
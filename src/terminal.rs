@@ -1,26 +1,29 @@
 use crate::model::{Model, ModelViewState, Screen};
-use ratatui::layout::Position;
-use ratatui::prelude::{Line, Rect, Style};
-use ratatui::widgets::{Block, List, ListState, Paragraph, Wrap};
-use ratatui::{
-    backend::{Backend, CrosstermBackend}, crossterm::{
-        terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-        ExecutableCommand,
-    },
-    Frame,
-    Terminal,
-};
-use serde_json::Value;
-use std::{cmp, io::stdout, panic};
+use ratatui::backend::{Backend, CrosstermBackend};
+use ratatui::crossterm::ExecutableCommand;
+use ratatui::crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use ratatui::crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode};
+use ratatui::layout::{Constraint, Layout, Margin, Position};
+use ratatui::prelude::{Line, Rect};
+use ratatui::widgets::{Block, Clear, List, ListState, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Wrap};
+use ratatui::{Frame, Terminal};
+use std::io::stdout;
+use std::{cmp, panic};
+
+/// smaller terminals get a notice instead of a garbled screen
+const MIN_WIDTH: u16 = 20;
+const MIN_HEIGHT: u16 = 4;
 
 pub fn init_terminal() -> anyhow::Result<Terminal<impl Backend>> {
     enable_raw_mode()?;
     stdout().execute(EnterAlternateScreen)?;
+    stdout().execute(EnableMouseCapture)?;
     let terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
     Ok(terminal)
 }
 
 pub fn restore_terminal() -> anyhow::Result<()> {
+    stdout().execute(DisableMouseCapture)?;
     stdout().execute(LeaveAlternateScreen)?;
     disable_raw_mode()?;
     Ok(())
@@ -29,6 +32,7 @@ pub fn restore_terminal() -> anyhow::Result<()> {
 pub fn install_panic_hook() {
     let original_hook = panic::take_hook();
     panic::set_hook(Box::new(move |panic_info| {
+        stdout().execute(DisableMouseCapture).unwrap();
         stdout().execute(LeaveAlternateScreen).unwrap();
         disable_raw_mode().unwrap();
         original_hook(panic_info);
@@ -39,19 +43,32 @@ pub fn view(
     model: &mut Model,
     frame: &mut Frame,
 ) {
-    if frame.area().width < 2 || frame.area().height < 2 {
-        return; // don't need to render anything here
+    if frame.area().width < MIN_WIDTH || frame.area().height < MIN_HEIGHT {
+        render_too_small_notice(frame);
+        return;
     }
 
     let mut view_state: ModelViewState = model.view_state.clone();
 
     match model.active_screen {
         Screen::Done => (),
+        Screen::Loading => render_loading_screen(model, frame),
         Screen::Main => render_main_screen(model, &mut view_state.main_window_list_state, frame),
+        Screen::Projection => render_projection_screen(model, &mut view_state.main_window_list_state, frame),
+        Screen::Histogram => render_histogram_screen(model, &mut view_state.histogram_list_state, frame),
         Screen::ObjectDetails => {
-            view_state.selected_object_detail_field_name = render_line_details_screen(model, &mut view_state.object_detail_list_state, frame)
+            view_state.selected_object_detail_field = render_line_details_screen(model, &mut view_state.object_detail_list_state, frame)
         }
-        Screen::ValueDetails => render_value_details_screen(model, &mut view_state.value_screen_vertical_scroll_offset, frame),
+        Screen::ValueDetails | Screen::RawLine => render_value_details_screen(
+            model,
+            &mut view_state.value_screen_vertical_scroll_offset,
+            &mut view_state.value_screen_horizontal_scroll_offset,
+            frame,
+        ),
+    }
+
+    if model.view_state.help_visible {
+        render_help(model, frame);
     }
 
     model.view_state = view_state;
@@ -59,19 +76,60 @@ pub fn view(
 
 /// Creates the screen border common to all screens.
 /// Returns the Border block and the Cursor position (if there is one)
-fn produce_screen_border<'a>(frame_area: Rect, model: &'a Model) -> (Block<'a>, Option<Position>) {
-    if model.has_find_task() {
+fn produce_screen_border<'a>(
+    frame_area: Rect,
+    model: &'a Model,
+) -> (Block<'a>, Option<Position>) {
+    if model.has_quit_confirmation() {
+        (
+            Block::bordered().title_bottom(model.render_quit_confirmation_line().left_aligned()),
+            None,
+        )
+    } else if model.has_find_task() {
         let find_line = model.render_find_task_line_left();
         let cursor_position = Some(Position::new((1 + find_line.width() - 4) as u16, frame_area.bottom() - 1));
-        (Block::bordered()
-             .title_bottom(find_line.left_aligned())
-             .title_bottom(model.render_find_task_line_right().right_aligned()),
-         cursor_position)
+        (
+            Block::bordered()
+                .title_bottom(find_line.left_aligned())
+                .title_bottom(model.render_find_task_line_right().right_aligned()),
+            cursor_position,
+        )
+    } else if model.has_export_task() {
+        let export_line = model.render_export_task_line_left();
+        let cursor_position = Some(Position::new((1 + export_line.width() - 4) as u16, frame_area.bottom() - 1));
+        (
+            Block::bordered()
+                .title_bottom(export_line.left_aligned())
+                .title_bottom(model.render_export_task_line_right().right_aligned()),
+            cursor_position,
+        )
+    } else if model.has_open_file_task() {
+        let open_file_line = model.render_open_file_task_line_left();
+        let cursor_position = Some(Position::new((1 + open_file_line.width() - 4) as u16, frame_area.bottom() - 1));
+        (Block::bordered().title_bottom(open_file_line.left_aligned()), cursor_position)
+    } else if model.has_projection_task() {
+        let projection_line = model.render_projection_task_line_left();
+        let cursor_position = Some(Position::new((1 + projection_line.width() - 4) as u16, frame_area.bottom() - 1));
+        (Block::bordered().title_bottom(projection_line.left_aligned()), cursor_position)
+    } else if model.has_histogram_task() {
+        let histogram_line = model.render_histogram_task_line_left();
+        let cursor_position = Some(Position::new((1 + histogram_line.width() - 4) as u16, frame_area.bottom() - 1));
+        (Block::bordered().title_bottom(histogram_line.left_aligned()), cursor_position)
+    } else if model.has_filter_task() {
+        let filter_line = model.render_filter_task_line_left();
+        let cursor_position = Some(Position::new((1 + filter_line.width() - 4) as u16, frame_area.bottom() - 1));
+        (
+            Block::bordered()
+                .title_bottom(filter_line.left_aligned())
+                .title_bottom(model.render_filter_task_line_right().right_aligned()),
+            cursor_position,
+        )
     } else {
-        (Block::bordered()
-             .title_bottom(Line::from(model.render_status_line_left()).left_aligned())
-             .title_bottom(Line::from(model.render_status_line_right()).right_aligned()),
-         None
+        (
+            Block::bordered()
+                .title_bottom(Line::from(model.render_status_line_left()).left_aligned())
+                .title_bottom(Line::from(model.render_status_line_right()).right_aligned()),
+            None,
         )
     }
 }
@@ -82,72 +140,255 @@ fn render_main_screen(
     frame: &mut Frame,
 ) {
     let (block, cursor_position) = produce_screen_border(frame.area(), model);
+    if model.raw_json_lines.is_empty() {
+        render_notice(block, "No JSON lines loaded", frame);
+        return;
+    }
+    let pinned_header = model.render_pinned_header();
+    // a pinned header replaces the table header
+    let table_header = pinned_header.is_none().then(|| model.render_table_header()).flatten();
+    let block = match (table_header, model.render_tab_bar()) {
+        // the table header is aligned with the columns
+        (Some(header), Some(tab_bar)) => block.title(header).title(tab_bar.right_aligned()),
+        (Some(header), None) => block.title(header),
+        (None, Some(tab_bar)) => block.title(tab_bar),
+        (None, None) => block,
+    };
     let json_line_list = List::new(model)
+        .highlight_style(model.props.theme.highlight_style)
+        .highlight_symbol(model.props.theme.highlight_symbol.as_str())
+        .scroll_padding(1);
+    if let Some(p) = cursor_position {
+        frame.set_cursor_position(p)
+    }
+    match pinned_header {
+        Some(header) => {
+            // the header stays, while the list scrolls beneath it
+            let [header_area, list_area] = Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(block.inner(frame.area()));
+            frame.render_widget(block, frame.area());
+            frame.render_widget(Paragraph::new(header).style(model.props.theme.header_style), header_area);
+            frame.render_stateful_widget(json_line_list, list_area, list_state);
+        }
+        None => frame.render_stateful_widget(json_line_list.block(block), frame.area(), list_state),
+    }
+    render_list_scrollbar(model.num_visible_lines(), list_state, frame);
+}
+
+fn render_projection_screen(
+    model: &Model,
+    list_state: &mut ListState,
+    frame: &mut Frame,
+) {
+    let (block, cursor_position) = produce_screen_border(frame.area(), model);
+    let block = block.title(model.projection().unwrap_or_default());
+    let projection_list = List::new(model.produce_projection_screen_lines())
         .block(block)
-        .highlight_style(Style::new().underlined())
-        .highlight_symbol("> ")
+        .highlight_style(model.props.theme.highlight_style)
+        .highlight_symbol(model.props.theme.highlight_symbol.as_str())
         .scroll_padding(1);
     if let Some(p) = cursor_position {
         frame.set_cursor_position(p)
     }
-    frame.render_stateful_widget(json_line_list, frame.area(), list_state);
+    frame.render_stateful_widget(projection_list, frame.area(), list_state);
+    render_list_scrollbar(model.num_visible_lines(), list_state, frame);
 }
 
-/// returns the key of the selected attribute
-fn render_line_details_screen(
+fn render_histogram_screen(
     model: &Model,
     list_state: &mut ListState,
     frame: &mut Frame,
-) -> Option<String> {
+) {
     let (block, cursor_position) = produce_screen_border(frame.area(), model);
-    let (list_items, keys_in_rendered_order) = model.produce_line_details_screen_content();
-    let list_items = list_items.into_iter()
-        .map(|e| Line::from(model.with_search_hits_marked(e)));
+    let block = block.title(model.render_histogram_title());
+    let histogram_lines = model.produce_histogram_screen_lines();
+    let num_histogram_lines = histogram_lines.len();
+    let histogram_list = List::new(histogram_lines)
+        .block(block)
+        .highlight_style(model.props.theme.highlight_style)
+        .highlight_symbol(model.props.theme.highlight_symbol.as_str())
+        .scroll_padding(1);
+    if let Some(p) = cursor_position {
+        frame.set_cursor_position(p)
+    }
+    frame.render_stateful_widget(histogram_list, frame.area(), list_state);
+    render_list_scrollbar(num_histogram_lines, list_state, frame);
+}
+
+/// keys which are not configurable; character keys work outside of text input only
+const FIXED_KEYS: [(&str, &str); 12] = [
+    ("Backspace", "delete the last character of a text input"),
+    ("e", "hide empty fields (main and detail screen)"),
+    ("t", "table view of the ordered fields (main screen)"),
+    ("o", "only the selected fields / all fields (main screen)"),
+    ("w", "cut long lines (main) / wrap lines (value screen)"),
+    ("v / y", "select a range of lines / copy it (main screen)"),
+    ("p", "pretty print lines (main) / JSON values (value screen)"),
+    ("d", "decode embedded JSON in a string (value screen)"),
+    ("r", "raw line as in the input (detail screen)"),
+    ("+ / -", "move a field in the field order (detail screen)"),
+    ("x", "suppress a field on the main screen (detail screen)"),
+    ("mouse", "click to select, click again to open; wheel to scroll"),
+];
+
+/// shows the effective key bindings in a box on top of the current screen
+fn render_help(
+    model: &Model,
+    frame: &mut Frame,
+) {
+    let mut lines: Vec<Line> = model
+        .props
+        .keys
+        .iter()
+        .map(|(action, keys)| {
+            let keys = keys.iter().map(|e| e.to_string()).collect::<Vec<_>>().join(", ");
+            Line::from(format!("{action:<24}{keys}"))
+        })
+        .collect();
+    lines.push(Line::default());
+    lines.extend(
+        FIXED_KEYS
+            .iter()
+            .map(|(key, description)| Line::from(format!("{key:<24}{description}"))),
+    );
+
+    let area = frame.area();
+    let width = cmp::min(area.width, 80);
+    let height = cmp::min(area.height, lines.len() as u16 + 2);
+    let help_area = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    let block = Block::bordered()
+        .title(" Key bindings ")
+        .title_bottom(Line::from(" any key closes the help ").right_aligned());
+    frame.render_widget(Clear, help_area);
+    frame.render_widget(Paragraph::new(lines).block(block), help_area);
+}
+
+fn render_loading_screen(
+    model: &Model,
+    frame: &mut Frame,
+) {
+    let (block, _) = produce_screen_border(frame.area(), model);
+    render_notice(block, &model.render_load_progress(), frame);
+}
+
+/// renders a single line of text in the middle of the screen
+fn render_notice(
+    block: Block,
+    text: &str,
+    frame: &mut Frame,
+) {
+    let [_, notice_area, _] =
+        Layout::vertical([Constraint::Fill(1), Constraint::Length(1), Constraint::Fill(1)]).areas(block.inner(frame.area()));
+    frame.render_widget(block, frame.area());
+    frame.render_widget(Paragraph::new(text).centered(), notice_area);
+}
+
+/// a centered notice – wrapped, as it doesn't fit into a single line on a tiny terminal
+fn render_too_small_notice(frame: &mut Frame) {
+    let text = format!("terminal too small (need at least {MIN_WIDTH}x{MIN_HEIGHT})");
+    let num_rows = text.len().div_ceil(frame.area().width.max(1) as usize) as u16;
+    let [_, notice_area, _] =
+        Layout::vertical([Constraint::Fill(1), Constraint::Length(num_rows), Constraint::Fill(1)]).areas(frame.area());
+    frame.render_widget(Paragraph::new(text).centered().wrap(Wrap { trim: true }), notice_area);
+}
+
+/// returns the path of the selected field
+fn render_line_details_screen(
+    model: &Model,
+    list_state: &mut ListState,
+    frame: &mut Frame,
+) -> Option<Vec<String>> {
+    let (mut block, cursor_position) = produce_screen_border(frame.area(), model);
+    if !model.view_state.object_detail_path.is_empty() {
+        block = block.title(model.render_object_detail_path());
+    }
+    if let Some(title) = model.render_duplicate_keys_title() {
+        block = block.title(title.right_aligned());
+    }
+    let (list_items, field_paths) = model.produce_line_details_screen_lines();
+    let num_list_items = list_items.len();
     let json_field_list = List::new(list_items)
         .block(block)
-        .highlight_style(Style::new().underlined())
+        .highlight_style(model.props.theme.highlight_style)
         .scroll_padding(1);
     if let Some(p) = cursor_position {
         frame.set_cursor_position(p)
     }
     frame.render_stateful_widget(json_field_list, frame.area(), list_state);
-    list_state.selected().map(|i| keys_in_rendered_order.get(i).unwrap().to_string())
+    render_list_scrollbar(num_list_items, list_state, frame);
+    list_state.selected().and_then(|i| field_paths.get(i).cloned())
 }
 
 fn render_value_details_screen(
     model: &Model,
     vertical_scroll_offset: &mut u16,
+    horizontal_scroll_offset: &mut u16,
     frame: &mut Frame,
 ) {
-    let line_idx = model.view_state.main_window_list_state.selected().expect("we should find a a selected line");
-    let raw_line = &model.raw_json_lines.lines[line_idx].content;
-    let field_name = model.view_state.selected_object_detail_field_name.as_ref().expect("should have a selected field");
-
-    let value = raw_line.parse::<serde_json::Value>().expect("invalid json");
-    let Value::Object(o) = value else {
-        panic!("should find a json object");
-    };
-
-    let field_value = o.get(field_name).expect("key should exist");
-    let text = match field_value {
-        Value::String(s) => s.clone(),
-        _ => format!("{field_value}")
-    };
+    let text_lines = model.produce_value_details_screen_rows();
 
     // correct scroll line offset – so that current text lines are always on the screen
     let page_len = frame.area().height.saturating_sub(2);
-    let max_reasonable_scroll_offset = (text.lines().count() as u16).saturating_sub(page_len);
+    let max_reasonable_scroll_offset = (text_lines.len() as u16).saturating_sub(page_len);
     *vertical_scroll_offset = cmp::min(*vertical_scroll_offset, max_reasonable_scroll_offset);
 
-    let (block, cursor_position) = produce_screen_border(frame.area(), model);
-    let paragraph = Paragraph::new(text)
-        .wrap(Wrap::default())
+    // same for the column offset – horizontal scrolling applies to unwrapped text only
+    let page_width = frame.area().width.saturating_sub(2);
+    let max_line_width = text_lines.iter().map(|e| e.width()).max().unwrap_or(0);
+    let max_reasonable_horizontal_offset = (max_line_width as u16).saturating_sub(page_width);
+    *horizontal_scroll_offset = match model.view_state.value_screen_wrap {
+        true => 0,
+        false => cmp::min(*horizontal_scroll_offset, max_reasonable_horizontal_offset),
+    };
+
+    let (mut block, cursor_position) = produce_screen_border(frame.area(), model);
+    if let Some(title) = model.render_value_details_title() {
+        block = block.title(title);
+    }
+    // wrapped already – so that the scroll offset counts rows
+    let paragraph = Paragraph::new(text_lines)
         .block(block)
-        .scroll((*vertical_scroll_offset, 0));
+        .scroll((*vertical_scroll_offset, *horizontal_scroll_offset));
 
     if let Some(p) = cursor_position {
         frame.set_cursor_position(p)
     }
 
     frame.render_widget(paragraph, frame.area());
+    if max_reasonable_scroll_offset > 0 {
+        render_scrollbar(max_reasonable_scroll_offset as usize + 1, *vertical_scroll_offset as usize, frame);
+    }
+}
+
+/// renders a scrollbar for a list (after the list itself), unless all of its items are on the screen
+fn render_list_scrollbar(
+    num_items: usize,
+    list_state: &ListState,
+    frame: &mut Frame,
+) {
+    let page_len = frame.area().height.saturating_sub(2) as usize;
+    if list_state.offset() > 0 || num_items > page_len {
+        render_scrollbar(num_items, list_state.selected().unwrap_or(0), frame);
+    }
+}
+
+/// renders a vertical scrollbar on the right border of the screen – for the given number of (scroll or selection) positions
+fn render_scrollbar(
+    num_positions: usize,
+    position: usize,
+    frame: &mut Frame,
+) {
+    let page_len = frame.area().height.saturating_sub(2) as usize;
+    let mut state = ScrollbarState::new(num_positions)
+        .viewport_content_length(page_len)
+        .position(position);
+    let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+        .begin_symbol(None)
+        .end_symbol(None);
+    frame.render_stateful_widget(scrollbar, frame.area().inner(Margin::new(0, 1)), &mut state);
 }
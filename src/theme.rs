@@ -0,0 +1,133 @@
+use ratatui::prelude::{Color, Modifier, Style};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::str::FromStr;
+
+/// Colors and styles of the UI.
+/// Colors are given by name (e.g. `red`, `light-blue`), as hex value (`#FF8000`) or as ANSI index (`208`).
+/// Styles are written as space separated words – modifiers, a foreground color and `on <background color>`,
+/// e.g. `bold yellow on blue`.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Theme {
+    /// find bar color when the search string was found
+    #[serde(with = "color_string")]
+    pub match_color: Color,
+    /// find bar color when the search string was not found
+    #[serde(with = "color_string")]
+    pub miss_color: Color,
+    /// style of the selected list line
+    #[serde(with = "style_string")]
+    pub highlight_style: Style,
+    /// marker in front of the selected list line
+    pub highlight_symbol: String,
+    /// style of the search hits within the text
+    #[serde(with = "style_string")]
+    pub search_hit_style: Style,
+    /// style of the lines in a selected range (main screen)
+    #[serde(with = "style_string")]
+    pub range_style: Style,
+    /// style of the bookmark marker in front of a line (main screen)
+    #[serde(with = "style_string")]
+    pub bookmark_style: Style,
+    /// style of the pinned header row (main screen)
+    #[serde(with = "style_string")]
+    pub header_style: Style,
+    #[serde(with = "color_string")]
+    pub string_value_color: Color,
+    #[serde(with = "color_string")]
+    pub number_value_color: Color,
+    /// color of booleans and null
+    #[serde(with = "color_string")]
+    pub literal_value_color: Color,
+    /// style of the lines with an error (or fatal) log level on the main screen
+    #[serde(with = "style_string")]
+    pub error_level_style: Style,
+    /// style of the lines with a warning log level on the main screen
+    #[serde(with = "style_string")]
+    pub warn_level_style: Style,
+    /// style of the lines with a debug (or trace) log level on the main screen
+    #[serde(with = "style_string")]
+    pub debug_level_style: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            match_color: Color::Green,
+            miss_color: Color::Red,
+            highlight_style: Style::new().underlined(),
+            highlight_symbol: "> ".to_string(),
+            search_hit_style: Style::new().on_yellow(),
+            range_style: Style::new().on_dark_gray(),
+            bookmark_style: Style::new().light_blue(),
+            header_style: Style::new().bold().reversed(),
+            string_value_color: Color::Green,
+            number_value_color: Color::Cyan,
+            literal_value_color: Color::Yellow,
+            error_level_style: Style::new().red(),
+            warn_level_style: Style::new().yellow(),
+            debug_level_style: Style::new().dim(),
+        }
+    }
+}
+
+fn parse_style(s: &str) -> Result<Style, String> {
+    let mut style = Style::new();
+    let mut words = s.split_whitespace();
+
+    while let Some(word) = words.next() {
+        if word.eq_ignore_ascii_case("on") {
+            let bg = words.next().ok_or_else(|| format!("missing background color in style '{s}'"))?;
+            style = style.bg(Color::from_str(bg).map_err(|_| format!("invalid color '{bg}'"))?);
+        } else if let Some(modifier) = Modifier::from_name(&word.to_ascii_uppercase()) {
+            style = style.add_modifier(modifier);
+        } else {
+            style = style.fg(Color::from_str(word).map_err(|_| format!("invalid color or modifier '{word}'"))?);
+        }
+    }
+
+    Ok(style)
+}
+
+fn format_style(style: &Style) -> String {
+    let mut words: Vec<String> = style.add_modifier.iter_names().map(|(name, _)| name.to_ascii_lowercase()).collect();
+    if let Some(fg) = style.fg {
+        words.push(fg.to_string());
+    }
+    if let Some(bg) = style.bg {
+        words.push(format!("on {bg}"));
+    }
+    words.join(" ")
+}
+
+mod color_string {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        color: &Color,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&color.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Color::from_str(&s).map_err(|_| serde::de::Error::custom(format!("invalid color '{s}'")))
+    }
+}
+
+mod style_string {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        style: &Style,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format_style(style))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Style, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        parse_style(&s).map_err(serde::de::Error::custom)
+    }
+}
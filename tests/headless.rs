@@ -0,0 +1,52 @@
+use json_lines_viewer::model::{Screen, ViewOptions};
+use json_lines_viewer::props::Props;
+use json_lines_viewer::{Message, Model, load_json_lines};
+use ratatui::prelude::Size;
+use std::fs::File;
+
+fn model() -> Model {
+    let file = File::open(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/events.jsonl")).unwrap();
+    let raw_lines = load_json_lines(file, "events.jsonl").unwrap();
+    Model::new(Props::default(), Size::new(120, 30), raw_lines, ViewOptions::default())
+}
+
+fn type_text(
+    mut model: Model,
+    text: &str,
+) -> Model {
+    for c in text.chars() {
+        model = model.step(Message::CharacterInput(c));
+    }
+    model
+}
+
+#[test]
+fn navigates_between_lines_and_screens() {
+    let model = model();
+    assert_eq!(model.num_visible_lines(), 4);
+    assert!(model.active_screen == Screen::Main);
+
+    let model = model.step(Message::ScrollDown).step(Message::ScrollDown);
+    assert_eq!(model.selected_raw_line_idx(), Some(2));
+
+    let model = model.step(Message::Enter);
+    assert!(model.active_screen == Screen::ObjectDetails);
+
+    let model = model.step(Message::Exit);
+    assert!(model.active_screen == Screen::Main);
+    assert_eq!(model.selected_raw_line_idx(), Some(2));
+
+    let model = model.step(Message::Last);
+    assert_eq!(model.selected_raw_line_idx(), Some(3));
+}
+
+#[test]
+fn filters_lines() {
+    let model = model().step(Message::OpenFilterTask);
+    assert!(model.has_filter_task());
+
+    let model = type_text(model, "level=ERROR").step(Message::Enter);
+    assert!(!model.has_filter_task());
+    assert_eq!(model.num_visible_lines(), 1);
+    assert_eq!(model.selected_raw_line_idx(), Some(3));
+}
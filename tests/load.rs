@@ -0,0 +1,83 @@
+use json_lines_viewer::load::{load_file, load_files};
+use json_lines_viewer::progress::LoadProgress;
+use std::path::Path;
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+#[test]
+fn loads_all_lines_of_a_jsonl_file() {
+    let path = Path::new(FIXTURES).join("events.jsonl");
+    let raw_lines = load_file(&path, None, None, false, &LoadProgress::new(1)).unwrap();
+
+    assert_eq!(raw_lines.lines.len(), 4);
+    assert_eq!(raw_lines.lines.iter().map(|e| e.line_nr).collect::<Vec<_>>(), [1, 2, 3, 4]);
+    assert_eq!(raw_lines.source_name(0).map(|e| e.to_string()).as_deref(), Some("events.jsonl"));
+}
+
+#[test]
+fn loads_a_jsonl_entry_inside_a_zip() {
+    let path = Path::new(FIXTURES).join("events.zip");
+    let raw_lines = load_file(&path, None, None, false, &LoadProgress::new(1)).unwrap();
+
+    // the README.txt entry isn't a JSON lines file and is skipped
+    assert_eq!(raw_lines.lines.len(), 4);
+    assert_eq!(raw_lines.num_sources(), 1);
+    assert_eq!(
+        raw_lines.source_name(0).map(|e| e.to_string()).as_deref(),
+        Some("events.zip/logs/events.jsonl")
+    );
+}
+
+#[test]
+fn files_share_the_line_limit() {
+    let files = [Path::new(FIXTURES).join("events.jsonl"), Path::new(FIXTURES).join("events.zip")];
+
+    // the earlier file takes its lines first – however the loading threads are scheduled
+    for _ in 0..20 {
+        let progress = LoadProgress::new(files.len());
+        let raw_lines = load_files(&files, Some(6), None, false, &progress).unwrap();
+        let source_names: Vec<String> = raw_lines
+            .lines
+            .iter()
+            .filter_map(|e| raw_lines.source_name(e.source_id).map(|e| e.to_string()))
+            .collect();
+        assert_eq!(source_names[..4], ["events.jsonl"; 4]);
+        assert_eq!(source_names[4..], ["events.zip/logs/events.jsonl"; 2]);
+        assert_eq!(progress.take_notes().len(), 1);
+    }
+
+    // a limit, which fits all lines exactly, drops nothing
+    let progress = LoadProgress::new(files.len());
+    let raw_lines = load_files(&files, Some(8), None, false, &progress).unwrap();
+    assert_eq!(raw_lines.lines.len(), 8);
+    assert!(progress.take_notes().is_empty());
+}
+
+#[test]
+fn loads_the_elements_of_a_json_array_up_to_the_line_limit() {
+    let path = Path::new(FIXTURES).join("array.json");
+    let raw_lines = load_file(&path, None, None, false, &LoadProgress::new(1)).unwrap();
+    assert_eq!(raw_lines.lines.len(), 3);
+    assert_eq!(raw_lines.lines[2].content, r#"{"level":"ERROR","message":"connection lost"}"#);
+
+    let progress = LoadProgress::new(1);
+    let raw_lines = load_file(&path, Some(2), None, false, &progress).unwrap();
+    assert_eq!(raw_lines.lines.len(), 2);
+    assert_eq!(progress.take_notes().len(), 1);
+}
+
+#[test]
+fn loads_json_lines_starting_with_an_array_as_lines() {
+    let path = Path::new(FIXTURES).join("arrays.jsonl");
+    let raw_lines = load_file(&path, Some(3), None, false, &LoadProgress::new(1)).unwrap();
+
+    assert_eq!(raw_lines.lines.len(), 2);
+    assert_eq!(raw_lines.lines[0].content, r#"["INFO", "service started"]"#);
+
+    // a line limit below the elements of the first array leaves them to be lines all the same
+    let progress = LoadProgress::new(1);
+    let raw_lines = load_file(&path, Some(1), None, false, &progress).unwrap();
+    assert_eq!(raw_lines.lines.len(), 1);
+    assert_eq!(raw_lines.lines[0].content, r#"["INFO", "service started"]"#);
+    assert_eq!(progress.take_notes().len(), 1);
+}